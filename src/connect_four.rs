@@ -1,242 +1,1068 @@
+use std::fmt;
 use std::io;
+use std::sync::{Arc, OnceLock};
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+mod ai;
+mod analysis;
+mod builder;
+mod capabilities;
+mod console;
+mod events;
+mod hash;
+mod messages;
+mod notation;
+mod play;
+mod puzzle;
+mod random;
+mod render;
+mod solver;
+#[cfg(feature = "svg")]
+mod svg;
+mod tactics;
+#[cfg(test)]
+mod test_rng;
+mod training;
+mod transform;
+mod transposition;
+
+pub use ai::*;
+pub use analysis::*;
+pub use builder::*;
+pub use capabilities::*;
+pub use events::*;
+pub use messages::*;
+pub use play::*;
+pub use puzzle::*;
+pub use random::*;
+pub use render::*;
+pub use solver::*;
+pub use tactics::*;
+pub use training::*;
+pub use transposition::*;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum Player {
     Red,
     Yellow,
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+impl Player {
+    /// Returns the other player.
+    pub fn opponent(self) -> Player {
+        match self {
+            Player::Red => Player::Yellow,
+            Player::Yellow => Player::Red,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum Cell {
     Player(Player),
     Empty,
 }
 
+/// A disc placed by `game_move`, recorded so the move can be taken back.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct MoveRecord {
+    pub col: usize,
+    pub row: usize,
+    pub player: Player,
+}
+
+#[derive(Clone)]
 pub struct Board {
     pub current_player: Player,
-    pub board: [[Cell; 7]; 6],
+    pub board: Vec<Vec<Cell>>,
+    pub move_count: usize,
+    pub moves: Vec<MoveRecord>,
+    /// See `set_fall_distance`, which keeps `open_columns` in step with it.
+    fall_distance: Option<usize>,
+    /// Names for Red and Yellow, in that order, shown by the interactive game instead of the
+    /// colours.
+    pub names: Option<(String, String)>,
+    /// Enables the pie rule: after the opening move the second player may swap sides with
+    /// `swap_sides` and take over the disc already played.
+    pub swap_rule: bool,
+    swapped: bool,
+    /// Lets players skip their turn with `pass`. Two passes in a row end the game in a
+    /// stalemate.
+    pub allow_pass: bool,
+    /// When set, the interactive game prints the evaluation of the position and the AI's
+    /// suggested move after every move, as coaching.
+    pub show_eval: bool,
+    /// The weights `evaluate` scores positions with.
+    pub eval_weights: EvalWeights,
+    /// Turns the printed board around for a player on the other side of a shared screen. Only
+    /// the drawing changes, columns keep their numbers.
+    pub flip_for_view: bool,
+    passes: usize,
+    last_was_pass: bool,
+    /// When false, completing a connect four doesn't end the game. The line is recorded in
+    /// `wins` instead and play carries on. The solver still scores the first line as the end of
+    /// the game. Defaults to true.
+    pub stop_on_win: bool,
+    wins: Vec<(Player, Vec<(usize, usize)>)>,
+    /// When set, a move completing several lines at once reports every one of them in `wins`
+    /// and `game_move_events` instead of just the first.
+    pub report_all_lines: bool,
+    /// When set, reaching the same position for the third time with `game_move` ends the game
+    /// in a stalemate. Discs only come off the board through `undo` and direct cell edits, so
+    /// standard games never repeat a position.
+    pub draw_on_repetition: bool,
+    /// The `current_hash` after each move in `moves`, in the same order.
+    positions: Vec<u64>,
+    /// The ply each cell was filled on by `game_move`, indexed like `board`.
+    placed_at: Vec<Vec<Option<usize>>>,
+    status: GameStatus,
+    rows: usize,
+    cols: usize,
+    win_length: usize,
+    /// Bit `i` is set while column `i` still has room, kept up to date by `update_cell` and
+    /// `set_fall_distance`.
+    open_columns: u64,
+    /// The Zobrist keys of every disc on the board XORed together, kept up to date by
+    /// `update_cell`. The player to move is mixed in by `current_hash`.
+    hash: u64,
+    /// Every window `windows` returns, built on first use and shared between clones since the
+    /// dimensions never change after construction.
+    window_cache: Arc<OnceLock<Vec<Window>>>,
 }
 
+/// The cells of one line of `win_length` on the board, as `(row, col)` pairs.
+type Window = Vec<(usize, usize)>;
+
 pub enum GameMoveResult {
     Valid,
     Won(Player),
     Stalemate,
 }
 
+/// The direction of a line of discs. `DiagonalUp` rises from left to right and `DiagonalDown`
+/// falls from left to right.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum WinDirection {
+    Horizontal,
+    Vertical,
+    DiagonalUp,
+    DiagonalDown,
+}
+
+/// The reasons a call to `game_move` can be rejected.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum MoveError {
+    InvalidColumn(usize),
+    ColumnFull(usize),
+    GameOver,
+    /// Returned by `pass` unless `allow_pass` is set.
+    PassNotAllowed,
+    /// Returned by `play_to_completion` when the game is still going after this many moves.
+    MoveLimitReached(usize),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveError::InvalidColumn(col) => write!(f, "Column {} is invalid", col),
+            MoveError::ColumnFull(col) => write!(f, "Column {} is full.", col),
+            MoveError::GameOver => write!(f, "The game is already over."),
+            MoveError::PassNotAllowed => write!(f, "Passing is not allowed."),
+            MoveError::MoveLimitReached(moves) => {
+                write!(f, "The game didn't end within {} moves.", moves)
+            }
+        }
+    }
+}
+
+/// The overall state of a game, kept up to date by every move.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum GameStatus {
+    InProgress,
+    Won(Player),
+    Stalemate,
+}
+
 impl Board {
     /// Creates a new board with the given starting player. The board is initialised to empty cells
-    /// which is a 6x7 grid.
+    /// which is a 6x7 grid. Use `BoardBuilder` for other sizes.
     pub fn new(starting_player: Player) -> Board {
+        Board::with_dimensions(starting_player, 6, 7, 4)
+    }
+
+    /// Creates a new 6x7 board with handicap discs already dropped. Each `(column, player)` pair
+    /// in `setup` is dropped in order and falls to the bottom of its column, without taking
+    /// turns, so one player can be given more discs than the other. The setup discs count
+    /// towards `move_count` but aren't part of the move history, so they can't be undone.
+    /// Returns an error if a column doesn't exist or would overflow. If the setup discs already
+    /// connect four, the game starts out won by their owner.
+    pub fn new_with_setup(
+        starting_player: Player,
+        setup: &[(usize, Player)],
+    ) -> Result<Board, MoveError> {
+        let mut board = Board::new(starting_player);
+        for &(col, player) in setup {
+            if col >= board.cols {
+                return Err(MoveError::InvalidColumn(col));
+            }
+            let row = board.row_available(col).ok_or(MoveError::ColumnFull(col))?;
+            board.update_cell(row, col, Cell::Player(player));
+            board.move_count += 1;
+        }
+        board.settle_status();
+        Ok(board)
+    }
+
+    fn with_dimensions(
+        starting_player: Player,
+        rows: usize,
+        cols: usize,
+        win_length: usize,
+    ) -> Board {
         Board {
             current_player: starting_player,
-            board: [[Cell::Empty; 7]; 6]
+            board: vec![vec![Cell::Empty; cols]; rows],
+            move_count: 0,
+            moves: Vec::new(),
+            fall_distance: None,
+            names: None,
+            swap_rule: false,
+            swapped: false,
+            allow_pass: false,
+            show_eval: false,
+            eval_weights: EvalWeights::default(),
+            flip_for_view: false,
+            passes: 0,
+            last_was_pass: false,
+            stop_on_win: true,
+            wins: Vec::new(),
+            report_all_lines: false,
+            draw_on_repetition: false,
+            positions: Vec::new(),
+            placed_at: vec![vec![None; cols]; rows],
+            status: GameStatus::InProgress,
+            rows,
+            cols,
+            win_length,
+            open_columns: u64::MAX >> (64 - cols),
+            hash: 0,
+            window_cache: Arc::new(OnceLock::new()),
         }
     }
 
-    pub fn run_game(&mut self) {
-        // Get user input 
+    /// Plays an interactive game in the terminal with the default English messages.
+    pub fn run_game(&mut self) -> GameStatus {
+        self.run_game_with(&Messages::default())
+    }
+
+    /// Plays an interactive game in the terminal, printing the given messages. Besides column
+    /// numbers, players can type `u` to undo the last move, `r` to restart, `resign` to give up,
+    /// `pass` to skip their turn when `allow_pass` is set and `q` or `quit` to leave. The game
+    /// also stops at the end of the input. Returns the status of the game when the loop ends.
+    pub fn run_game_with(&mut self, messages: &Messages) -> GameStatus {
+        // Get user input
         loop {
             self.print();
-            println!("Player {:?}, enter a move: ", self.current_player);
+            println!(
+                "{}",
+                messages::fill(
+                    &messages.prompt,
+                    &[("player", self.player_name(self.current_player))]
+                )
+            );
             let mut input = String::new();
 
-            if let Err(e) = io::stdin().read_line(&mut input) {
-                println!("Error getting input: {}", e);
-            } else {
-                match input.trim().parse::<usize>() {
-                    Ok(n) => {
-                        if n > 7 || n < 1 {
-                            println!("Column {} is invalid", n);
-                        } else {
-                            match self.game_move(n - 1) {
-                                Ok(game_move_result) => {
-                                    match game_move_result {
-                                        GameMoveResult::Valid => (),
-                                        GameMoveResult::Won(p) => {
-                                            println!("{:?} has a connect 4!\n", p);
-                                            self.print();
-                                            return;
-                                        }
-                                        GameMoveResult::Stalemate => {
-                                            println!("Gameover, Stalemate");
-                                            return;
-                                        }
-                                    }
-                                }
-                                Err(e) => println!("{}", e),
+            match io::stdin().read_line(&mut input) {
+                // The input has run out, as when a piped script ends, so stop like `quit`
+                Ok(0) => return self.status(),
+                Ok(_) => (),
+                Err(e) => {
+                    println!("{}", messages::fill(&messages.input_error, &[("error", e)]));
+                    continue;
+                }
+            }
+            match input.trim() {
+                "q" | "quit" => return self.status(),
+                "u" => {
+                    if self.undo().is_none() {
+                        println!("{}", messages.nothing_to_undo);
+                    }
+                    continue;
+                }
+                "r" => {
+                    while self.undo().is_some() {}
+                    continue;
+                }
+                "resign" => {
+                    if let GameStatus::Won(p) = self.resign() {
+                        println!(
+                            "{}",
+                            messages::fill(
+                                &messages.resign,
+                                &[
+                                    ("loser", self.player_name(p.opponent())),
+                                    ("winner", self.player_name(p)),
+                                ]
+                            )
+                        );
+                    }
+                    return self.status();
+                }
+                "pass" => {
+                    match self.pass() {
+                        Ok(GameStatus::Stalemate) => {
+                            println!("{}", messages.stalemate);
+                            return self.status();
+                        }
+                        Ok(_) => (),
+                        Err(e) => println!("{}", messages.move_error(e)),
+                    }
+                    continue;
+                }
+                _ => (),
+            }
+            match input.trim().parse::<usize>() {
+                Ok(n) => match self.game_move_one_based(n) {
+                    Ok(game_move_result) => match game_move_result {
+                        GameMoveResult::Valid => {
+                            if self.can_swap() {
+                                self.offer_swap(messages);
                             }
+                            if self.show_eval {
+                                println!("{}", self.eval_message(messages));
+                            }
+                        }
+                        GameMoveResult::Won(p) => {
+                            let record = self.moves[self.moves.len() - 1];
+                            let (direction, _) = self.winning_line(record.row, record.col).unwrap();
+                            println!(
+                                "{}",
+                                messages.win_message(p, &self.player_name(p), direction)
+                            );
+                            self.print();
+                            return self.status();
+                        }
+                        GameMoveResult::Stalemate => {
+                            println!("{}", messages.stalemate);
+                            return self.status();
                         }
                     },
-                    Err(_) => {
-                        println!("Please enter a valid column number");
-                    }
+                    Err(e) => println!("{}", messages.move_error(e)),
+                },
+                Err(_) => {
+                    println!("{}", messages.invalid_input);
                 }
             }
         }
     }
 
-    pub fn game_move(&mut self, col: usize) -> Result<GameMoveResult, String> {
-        // First check for stalemate
-        let mut stalemate = true;
-        for i in 0..7 {
-            if let Some(_) = self.row_available(i) {
-                stalemate = false;
-                break;
-            }
+    /// Asks the second player whether they want to swap sides and swaps if they answer yes.
+    fn offer_swap(&mut self, messages: &Messages) {
+        let opening = self.current_player.opponent();
+        println!(
+            "{}",
+            messages::fill(
+                &messages.swap_offer,
+                &[
+                    ("player", self.player_name(self.current_player)),
+                    ("opener", self.player_name(opening)),
+                ]
+            )
+        );
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).is_ok() && input.trim() == "y" {
+            self.swap_sides();
         }
-        if stalemate {
+    }
+
+    /// Fills in the coaching message shown when `show_eval` is set, from the point of view of
+    /// the player to move.
+    fn eval_message(&self, messages: &Messages) -> String {
+        self.eval_message_with(messages, self.best_move(DEFAULT_DEPTH))
+    }
+
+    /// Fills in the coaching message like `eval_message`, suggesting a column the caller has
+    /// already searched for.
+    fn eval_message_with(&self, messages: &Messages, suggestion: Option<usize>) -> String {
+        let player = self.current_player;
+        let column = suggestion.map_or("-".to_string(), |col| (col + 1).to_string());
+        messages::fill(
+            &messages.eval,
+            &[
+                ("player", self.player_name(player)),
+                ("score", self.evaluate(player).to_string()),
+                (
+                    "percent",
+                    format!("{:.0}", self.win_probability(player) * 100.0),
+                ),
+                ("column", column),
+            ],
+        )
+    }
+
+    /// Returns the name shown for a player in the interactive game, falling back to the colour
+    /// when no names are set.
+    fn player_name(&self, player: Player) -> String {
+        match (&self.names, player) {
+            (Some((red, _)), Player::Red) => red.clone(),
+            (Some((_, yellow)), Player::Yellow) => yellow.clone(),
+            (None, _) => format!("{:?}", player),
+        }
+    }
+
+    /// Drops a disc for the current player into the given column. Columns are zero-based, so 0
+    /// is the leftmost column; every method on the board uses this convention except
+    /// `game_move_one_based`.
+    pub fn game_move(&mut self, col: usize) -> Result<GameMoveResult, MoveError> {
+        if self.status != GameStatus::InProgress {
+            return Err(MoveError::GameOver);
+        }
+        if col >= self.cols {
+            return Err(MoveError::InvalidColumn(col));
+        }
+
+        // First check for stalemate
+        if self.open_columns == 0 {
+            self.status = GameStatus::Stalemate;
             return Ok(GameMoveResult::Stalemate)
         }
 
         if let Some(row) = self.row_available(col) {
             self.update_cell(row, col, Cell::Player(self.current_player));
-            if self.has_won(row, col) {
-                return Ok(GameMoveResult::Won(self.current_player))
+            self.placed_at[row][col] = Some(self.next_ply());
+            self.last_was_pass = false;
+            self.move_count += 1;
+            self.moves.push(MoveRecord {
+                col,
+                row,
+                player: self.current_player,
+            });
+            let mut lines = self.winning_lines(row, col);
+            if !lines.is_empty() {
+                if self.stop_on_win {
+                    self.positions.push(self.current_hash());
+                    self.status = GameStatus::Won(self.current_player);
+                    return Ok(GameMoveResult::Won(self.current_player))
+                }
+                if !self.report_all_lines {
+                    lines.truncate(1);
+                }
+                for (_, line) in lines {
+                    self.wins.push((self.current_player, line));
+                }
+            }
+            self.current_player = self.current_player.opponent();
+            self.positions.push(self.current_hash());
+            if self.open_columns == 0 || (self.draw_on_repetition && self.repetitions() >= 3) {
+                self.status = GameStatus::Stalemate;
+                return Ok(GameMoveResult::Stalemate);
             }
-            self.current_player = if self.current_player == Player::Yellow {
-                Player::Red
-            } else {
-                Player::Yellow
-            };
             Ok(GameMoveResult::Valid)
         } else {
-            Err(format!("Column {} is full.", col))
+            Err(MoveError::ColumnFull(col))
+        }
+    }
+
+    /// Returns a copy of the board with the move played, leaving this board untouched.
+    pub fn apply(&self, col: usize) -> Result<Board, MoveError> {
+        let mut board = self.clone();
+        board.game_move(col)?;
+        Ok(board)
+    }
+
+    /// Takes back the last move played with `game_move`, clearing its cell and handing the turn
+    /// back to the player who made it. Returns the undone move, or None if there is nothing to
+    /// undo.
+    pub fn undo(&mut self) -> Option<MoveRecord> {
+        let record = self.moves.pop()?;
+        self.positions.pop();
+        self.update_cell(record.row, record.col, Cell::Empty);
+        self.placed_at[record.row][record.col] = None;
+        self.last_was_pass = false;
+        // Only wins completed by this move can include its cell
+        while self
+            .wins
+            .last()
+            .is_some_and(|(_, line)| line.contains(&(record.row, record.col)))
+        {
+            self.wins.pop();
+        }
+        self.current_player = record.player;
+        self.move_count -= 1;
+        self.status = GameStatus::InProgress;
+        if self.moves.is_empty() && self.swapped {
+            self.swap_sides_unchecked();
         }
+        Some(record)
+    }
+
+    /// Returns the cell, as `(row, col)`, and the player of the disc the next `undo` would take
+    /// back, or None if there is nothing to undo. The board is left untouched.
+    pub fn peek_undo(&self) -> Option<(usize, usize, Player)> {
+        self.moves
+            .last()
+            .map(|record| (record.row, record.col, record.player))
+    }
+
+    /// Returns true while the pie rule lets the second player swap sides, which is only right
+    /// after the opening move and only once.
+    pub fn can_swap(&self) -> bool {
+        self.swap_rule
+            && !self.swapped
+            && self.moves.len() == 1
+            && self.status == GameStatus::InProgress
     }
 
+    /// Swaps which player controls each colour under the pie rule. The discs and the colour to
+    /// move stay as they are, so the player who opened now moves next with the other colour,
+    /// and the names are exchanged to match. Returns false without changing anything when
+    /// `can_swap` doesn't allow it. Undoing the opening move reverses the swap.
+    pub fn swap_sides(&mut self) -> bool {
+        if !self.can_swap() {
+            return false;
+        }
+        self.swap_sides_unchecked();
+        true
+    }
+
+    /// Returns true if the players have swapped sides under the pie rule.
+    pub fn swapped(&self) -> bool {
+        self.swapped
+    }
+
+    fn swap_sides_unchecked(&mut self) {
+        self.swapped = !self.swapped;
+        if let Some((red, yellow)) = self.names.take() {
+            self.names = Some((yellow, red));
+        }
+    }
+
+    /// Returns the connect fours completed while `stop_on_win` was off, in the order they were
+    /// made, with the player who made each. A move that completes several lines at once is
+    /// recorded once, with the first line found through the disc, unless `report_all_lines` is
+    /// set.
+    pub fn wins(&self) -> &[(Player, Vec<(usize, usize)>)] {
+        &self.wins
+    }
+
+    /// Returns true if the current position, discs and player to move alike, was already
+    /// reached by an earlier move this game.
+    pub fn position_repeated(&self) -> bool {
+        self.repetitions() >= 2
+    }
+
+    /// Counts the moves this game that led to the current position.
+    fn repetitions(&self) -> usize {
+        let hash = self.current_hash();
+        self.positions.iter().filter(|&&h| h == hash).count()
+    }
+
+    /// Ends the game for a position set up without `game_move`: won by the owner of any connect
+    /// four on the board, otherwise a stalemate if the board is full.
+    fn settle_status(&mut self) {
+        if let Some(winner) = self.find_winner() {
+            self.status = GameStatus::Won(winner);
+        } else if self.is_full() {
+            self.status = GameStatus::Stalemate;
+        }
+    }
+
+    /// Returns the current state of the game.
+    pub fn status(&self) -> GameStatus {
+        self.status
+    }
+
+    /// Returns the outcome of the game for the given player as a reward signal: 1.0 for a win,
+    /// -1.0 for a loss and 0.0 for a stalemate or a game still in progress.
+    pub fn reward(&self, player: Player) -> f32 {
+        match self.status {
+            GameStatus::Won(p) if p == player => 1.0,
+            GameStatus::Won(_) => -1.0,
+            GameStatus::InProgress | GameStatus::Stalemate => 0.0,
+        }
+    }
+
+    /// The current player gives up, handing the win to their opponent. Has no effect if the game
+    /// is already over. Returns the resulting status.
+    pub fn resign(&mut self) -> GameStatus {
+        if self.status == GameStatus::InProgress {
+            self.status = GameStatus::Won(self.current_player.opponent());
+        }
+        self.status
+    }
+
+    /// The current player skips their turn without dropping a disc, when `allow_pass` is set.
+    /// If the previous turn was also a pass the game ends in a stalemate. Passes aren't part of
+    /// the move history, so `undo` can't take them back. Returns the resulting status.
+    pub fn pass(&mut self) -> Result<GameStatus, MoveError> {
+        if !self.allow_pass {
+            return Err(MoveError::PassNotAllowed);
+        }
+        if self.status != GameStatus::InProgress {
+            return Err(MoveError::GameOver);
+        }
+        self.passes += 1;
+        if self.last_was_pass {
+            self.status = GameStatus::Stalemate;
+        } else {
+            self.last_was_pass = true;
+            self.current_player = self.current_player.opponent();
+        }
+        Ok(self.status)
+    }
+
+    /// Returns how many times the players have passed this game.
+    pub fn pass_count(&self) -> usize {
+        self.passes
+    }
+
+    /// Iterates over the moves played so far as (player, column) pairs, oldest first.
+    pub fn history(&self) -> impl Iterator<Item = (Player, usize)> + '_ {
+        self.moves.iter().map(|record| (record.player, record.col))
+    }
+
+    /// Same as `game_move`, spelled out for callers that want to be explicit about using
+    /// zero-based columns.
+    pub fn game_move_zero_based(&mut self, col: usize) -> Result<GameMoveResult, MoveError> {
+        self.game_move(col)
+    }
+
+    /// Drops a disc using a one-based column as typed by a player, so 1 is the leftmost column.
+    /// Error messages also refer to the one-based column.
+    pub fn game_move_one_based(&mut self, col: usize) -> Result<GameMoveResult, MoveError> {
+        if !(1..=self.cols).contains(&col) {
+            return Err(MoveError::InvalidColumn(col));
+        }
+        self.game_move(col - 1).map_err(|e| match e {
+            MoveError::ColumnFull(_) => MoveError::ColumnFull(col),
+            e => e,
+        })
+    }
+
+    /// Returns the ply number the next disc dropped will occupy, starting from 1.
+    pub fn next_ply(&self) -> usize {
+        self.move_count + 1
+    }
+
+    /// Returns the ply, starting from 1, on which the disc in the given cell was dropped with
+    /// `game_move`, or None if the cell is empty or was filled some other way.
+    pub fn placed_ply(&self, row: usize, col: usize) -> Option<usize> {
+        self.placed_at[row][col]
+    }
+
+    /// Returns true if the next ply is odd, i.e. it is the starting player's turn.
+    pub fn ply_parity(&self) -> bool {
+        self.next_ply() % 2 == 1
+    }
+
+    /// Returns the row a disc dropped into the given column would land in. With a
+    /// `fall_distance` the disc stops after falling that many cells, or earlier if it lands on
+    /// the highest disc in the column, so it can never land in an occupied cell. In that mode a
+    /// column counts as full once its top row is taken, even if there are gaps below.
     fn row_available(&self, col: usize) -> Option<usize> {
-        for i in 0..6 {
-            if self.board[i][col] == Cell::Empty {
-                return Some(i);
+        match self.fall_distance {
+            None => (0..self.rows).find(|&i| self.board[i][col] == Cell::Empty),
+            Some(distance) => {
+                let floor = self.column_top(col).map_or(0, |(row, _)| row + 1);
+                if floor >= self.rows {
+                    None
+                } else {
+                    Some(floor.max(self.rows.saturating_sub(distance.max(1))))
+                }
             }
         }
-        None
     }
 
-    /// Prints the current state of the board
-    pub fn print(&self) {
-        for row in self.board.iter().rev() {
-            for cell in row {
-                match cell {
-                    Cell::Player(player) => {
-                        match player {
-                            Player::Red => print!("😈  "),
-                            Player::Yellow => print!("😳  ")
+    /// Returns the number of discs stacked in the given column.
+    pub fn column_height(&self, col: usize) -> usize {
+        (0..self.rows)
+            .filter(|&i| self.board[i][col] != Cell::Empty)
+            .count()
+    }
+
+    /// Returns the highest occupied row of the given column along with its cell, or None if the
+    /// column is empty.
+    pub fn column_top(&self, col: usize) -> Option<(usize, Cell)> {
+        (0..self.rows)
+            .rev()
+            .find(|&i| self.board[i][col] != Cell::Empty)
+            .map(|i| (i, self.board[i][col]))
+    }
+
+    /// Returns a copy of every cell in row-major order. The origin is the bottom-left cell, so
+    /// the cell at `(row, col)` is found at index `row * cols + col`, with row 0 being the bottom
+    /// row and column 0 the leftmost.
+    pub fn as_flat(&self) -> Vec<Cell> {
+        self.board.iter().flatten().copied().collect()
+    }
+
+    /// Returns the first disc found sitting above an empty cell, which gravity wouldn't allow,
+    /// as `(row, col)`. Columns are checked from left to right and each from the bottom up.
+    /// Returns None if every disc is supported.
+    pub fn has_floating_discs(&self) -> Option<(usize, usize)> {
+        (0..self.cols).find_map(|col| {
+            (1..self.rows)
+                .find(|&row| {
+                    self.board[row][col] != Cell::Empty && self.board[row - 1][col] == Cell::Empty
+                })
+                .map(|row| (row, col))
+        })
+    }
+
+    /// Pulls the discs in every column down to close any gaps under them, keeping their order
+    /// within the column, so a board with floating discs obeys gravity again. Moves in the
+    /// history and their ply numbers follow the discs to their new rows.
+    pub fn compact_gravity(&mut self) {
+        for col in 0..self.cols {
+            let mut floor = 0;
+            for row in 0..self.rows {
+                let cell = self.board[row][col];
+                if cell == Cell::Empty {
+                    continue;
+                }
+                if row != floor {
+                    self.update_cell(row, col, Cell::Empty);
+                    self.update_cell(floor, col, cell);
+                    self.placed_at[floor][col] = self.placed_at[row][col].take();
+                    for record in self.moves.iter_mut() {
+                        if (record.row, record.col) == (row, col) {
+                            record.row = floor;
                         }
                     }
-                    Cell::Empty => {
-                        print!(" _  ")
-                    }
                 }
+                floor += 1;
             }
-            println!()
         }
-        println!();
-        for i in 1..8 {
-            print!(" {}  ", i);
+    }
+
+    /// Returns the columns that still have room for another disc, in ascending order.
+    pub fn legal_moves(&self) -> Vec<usize> {
+        (0..self.cols)
+            .filter(|&col| self.open_columns & (1 << col) != 0)
+            .collect()
+    }
+
+    /// Returns true if a disc can be dropped into the column, meaning it exists and has room.
+    pub fn can_play(&self, col: usize) -> bool {
+        col < self.cols && self.open_columns & (1 << col) != 0
+    }
+
+    /// Returns the size of the grid as `(rows, cols)`.
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// Returns how many discs in a row win the game.
+    pub fn win_length(&self) -> usize {
+        self.win_length
+    }
+
+    /// Returns how many cells from the top of the board discs fall, or None when they fall all
+    /// the way down to the stack.
+    pub fn fall_distance(&self) -> Option<usize> {
+        self.fall_distance
+    }
+
+    /// Makes discs only fall this many cells from the top of the board instead of all the way
+    /// down to the stack, or restores normal gravity with None. Which columns count as full
+    /// depends on the mode, so they are worked out again for the discs already on the board.
+    pub fn set_fall_distance(&mut self, fall_distance: Option<usize>) {
+        self.fall_distance = fall_distance;
+        for col in 0..self.cols {
+            if self.row_available(col).is_some() {
+                self.open_columns |= 1 << col;
+            } else {
+                self.open_columns &= !(1 << col);
+            }
         }
-        println!("\n");
     }
 
-    pub fn update_cell(&mut self, row: usize, col: usize, cell: Cell) {
-        self.board[row][col] = cell;
+    /// Returns true when every column is full.
+    pub fn is_full(&self) -> bool {
+        self.open_columns == 0
     }
 
-    pub fn has_won(&self, row: usize, col: usize) -> bool {
-        let mut connection_count = 0;
-        // Checking row
-        for i in 0..7 {
-            if let Cell::Player(p) = self.board[row][i] {
-                if p == self.current_player {
-                    connection_count += 1;
-                    if connection_count == 4 {
-                        return true;
-                    }
-                } else {
-                    connection_count = 0;
+    /// Returns the share of cells holding a disc, from 0.0 for an empty board to 1.0 for a full
+    /// one.
+    pub fn fullness(&self) -> f64 {
+        let discs: usize = (0..self.cols).map(|col| self.column_height(col)).sum();
+        discs as f64 / (self.rows * self.cols) as f64
+    }
+
+    /// Returns true if the game can't go on, either because it is over, the board is full or a
+    /// connect four is already on the board. The cheap checks come first, so the board is only
+    /// scanned for a winner when the status doesn't already settle it, such as for discs placed
+    /// with `update_cell`.
+    pub fn is_terminal(&self) -> bool {
+        self.status != GameStatus::InProgress || self.is_full() || self.find_winner().is_some()
+    }
+
+    /// Returns the playable columns as a bitmask with bit `i` set while column `i` has room,
+    /// without allocating. Only the first eight columns fit, which covers the standard board.
+    pub fn legal_mask(&self) -> u8 {
+        self.open_columns as u8
+    }
+
+    /// Returns the legal moves ordered from the centre column outwards, taking the lower index
+    /// first when two columns are the same distance from the centre. Central moves take part in
+    /// the most lines, so searching them first finds good moves sooner.
+    pub fn ordered_moves(&self) -> Vec<usize> {
+        let mut moves = self.legal_moves();
+        let centre = (self.cols / 2) as i32;
+        moves.sort_by_key(|&col| ((col as i32 - centre).abs(), col));
+        moves
+    }
+
+    /// Returns the columns where the given player would connect four by dropping a disc now.
+    pub fn winning_moves(&self, player: Player) -> Vec<usize> {
+        self.legal_moves()
+            .into_iter()
+            .filter(|&col| {
+                let mut board = self.clone();
+                let row = board.row_available(col).unwrap();
+                board.update_cell(row, col, Cell::Player(player));
+                board.has_won(row, col)
+            })
+            .collect()
+    }
+
+    /// Returns the player that would have a connect four if the disc at the given cell were
+    /// removed and the discs above it fell down one row, as in the pop out variant. The board
+    /// itself is left untouched.
+    pub fn win_if_removed(&self, row: usize, col: usize) -> Option<Player> {
+        if self.board[row][col] == Cell::Empty {
+            return None;
+        }
+
+        let mut board = self.clone();
+        for i in row..self.rows - 1 {
+            board.board[i][col] = board.board[i + 1][col];
+        }
+        board.board[self.rows - 1][col] = Cell::Empty;
+
+        // Only the discs that fell can be part of a new line
+        for i in row..self.rows {
+            if let Cell::Player(p) = board.board[i][col] {
+                if board.has_won(i, col) {
+                    return Some(p);
                 }
-            } else {
-                connection_count = 0;
             }
         }
-        // Checking column
-        connection_count = 0;
-        for i in 0..6 {
-            if let Cell::Player(p) = self.board[i][col] {
-                if p == self.current_player {
-                    connection_count += 1;
-                    if connection_count == 4 {
-                        return true;
+        None
+    }
+
+    /// Returns the player owning a complete line of `win_length` discs anywhere on the board, or
+    /// None if there isn't one.
+    pub fn find_winner(&self) -> Option<Player> {
+        self.windows().iter().find_map(|window| {
+            let (row, col) = window[0];
+            let first = self.board[row][col];
+            match first {
+                Cell::Player(p) if window.iter().all(|&(r, c)| self.board[r][c] == first) => {
+                    Some(p)
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// Counts the lines of `win_length` cells the player could still complete, meaning they hold
+    /// none of the opponent's discs.
+    pub fn open_lines(&self, player: Player) -> usize {
+        self.windows()
+            .iter()
+            .filter(|window| self.window_open_for(window, player))
+            .count()
+    }
+
+    /// Returns every line of `win_length` cells on the board that could hold a connect four, in
+    /// any direction. The list is only worked out the first time it is asked for.
+    fn windows(&self) -> &[Window] {
+        self.window_cache.get_or_init(|| {
+            let reach = self.win_length as isize - 1;
+            let mut windows = Vec::new();
+            for row in 0..self.rows {
+                for col in 0..self.cols {
+                    for (dr, dc) in [(0, 1), (1, 0), (1, 1), (1, -1)] {
+                        let end_row = row as isize + reach * dr;
+                        let end_col = col as isize + reach * dc;
+                        if !self.in_bounds(end_row, end_col) {
+                            continue;
+                        }
+                        windows.push(
+                            (0..self.win_length as isize)
+                                .map(|i| {
+                                    (
+                                        (row as isize + i * dr) as usize,
+                                        (col as isize + i * dc) as usize,
+                                    )
+                                })
+                                .collect(),
+                        );
                     }
-                } else {
-                    connection_count = 0;
                 }
-            } else {
-                connection_count = 0;
             }
+            windows
+        })
+    }
+
+    /// Prints the current state of the board
+    pub fn print(&self) {
+        print!("{}", self.render());
+    }
+
+    pub fn update_cell(&mut self, row: usize, col: usize, cell: Cell) {
+        if let Cell::Player(player) = self.board[row][col] {
+            self.hash ^= self.zobrist_key(row, col, player);
         }
-        // Checking north east diagonal
-        // First go to the bottom left most point of the diagonal
-        let mut coord = (row, col);
-        if row <= col {
-            coord.0 = 0;
-            coord.1 -= row;
-        } else {
-            coord.0 -= col;
-            coord.1 = 0;
+        if let Cell::Player(player) = cell {
+            self.hash ^= self.zobrist_key(row, col, player);
         }
-        connection_count = 0;
-        let range = if 6 - coord.0 < 7 - coord.1 {
-            6 - coord.0
+        self.board[row][col] = cell;
+        if self.row_available(col).is_some() {
+            self.open_columns |= 1 << col;
         } else {
-            7 - coord.1
-        };
-        for i in 0..range {
-            if let Cell::Player(p) = self.board[coord.0 + i][coord.1 + i] {
-                if p == self.current_player {
-                    connection_count += 1;
-                    if connection_count == 4 {
-                        return true;
-                    }
-                } else {
-                    connection_count = 0;
-                }
-            } else {
-                connection_count = 0;
-            }
+            self.open_columns &= !(1 << col);
         }
+    }
 
-        // south east diagonal
-        coord = (row, col);
-        if (col + row) as i32 - 6 > 0 {
-            coord.0 -= 6 - col;
-            coord.1 = 6;
-        } else {
-            coord.0 = 0;
-            coord.1 += row;
+    /// Places a disc for the given player without applying gravity or switching turns, for
+    /// setting up positions in tests. Panics if the cell is outside the board.
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn force_place(&mut self, row: usize, col: usize, player: Player) {
+        assert!(
+            row < self.rows && col < self.cols,
+            "Cell ({}, {}) is outside the {}x{} board",
+            row,
+            col,
+            self.rows,
+            self.cols
+        );
+        self.update_cell(row, col, Cell::Player(player));
+    }
+
+    /// Returns true if the disc at the given cell is part of a connect four.
+    pub fn has_won(&self, row: usize, col: usize) -> bool {
+        self.winning_line(row, col).is_some()
+    }
+
+    /// Returns the direction and cells of a connect four running through the player's disc at
+    /// the given cell, or None if the cell doesn't hold a disc of theirs that is part of one.
+    pub fn win_details(
+        &self,
+        row: usize,
+        col: usize,
+        player: Player,
+    ) -> Option<(WinDirection, Vec<(usize, usize)>)> {
+        if self.board[row][col] != Cell::Player(player) {
+            return None;
         }
-        connection_count = 0;
-        let range = if 5 - coord.0 < coord.1 {
-            5 - coord.0
-        } else {
-            coord.1 + 1
+        self.winning_line(row, col)
+    }
+
+    /// Returns every connect four running through the player's disc at the given cell, one per
+    /// direction in the order of `WinDirection`, so a move that completes several lines at once
+    /// reports them all. Empty if the cell doesn't hold a disc of theirs that is part of one.
+    pub fn all_win_details(
+        &self,
+        row: usize,
+        col: usize,
+        player: Player,
+    ) -> Vec<(WinDirection, Vec<(usize, usize)>)> {
+        if self.board[row][col] != Cell::Player(player) {
+            return Vec::new();
+        }
+        self.winning_lines(row, col)
+    }
+
+    /// Returns the first of `winning_lines`, or None if the disc isn't part of a connect four.
+    fn winning_line(&self, row: usize, col: usize) -> Option<(WinDirection, Vec<(usize, usize)>)> {
+        self.winning_lines(row, col).into_iter().next()
+    }
+
+    /// Returns the direction and cells of every connect four running through the disc at the
+    /// given cell, with the cells ordered from the left end of the line (or the bottom for
+    /// vertical lines).
+    fn winning_lines(&self, row: usize, col: usize) -> Vec<(WinDirection, Vec<(usize, usize)>)> {
+        let player = match self.board[row][col] {
+            Cell::Player(p) => p,
+            Cell::Empty => return Vec::new(),
         };
-        for i in 0..range {
-            if let Cell::Player(p) = self.board[coord.0 + i][coord.1 - i] {
-                if p == self.current_player {
-                    connection_count += 1;
-                    if connection_count == 4 {
-                        return true;
-                    }
-                } else {
-                    connection_count = 0;
-                }
-                
-            } else {
-                connection_count = 0;
+        let directions = [
+            (WinDirection::Horizontal, 0, 1),
+            (WinDirection::Vertical, 1, 0),
+            (WinDirection::DiagonalUp, 1, 1),
+            (WinDirection::DiagonalDown, -1, 1),
+        ];
+        let mut lines = Vec::new();
+        // Count outwards from the disc in both directions of each line through it
+        for (direction, dr, dc) in directions {
+            let back = self.connected_count(row, col, -dr, -dc, player) as isize;
+            let forward = self.connected_count(row, col, dr, dc, player) as isize;
+            if (1 + back + forward) as usize >= self.win_length {
+                let line = (-back..=forward)
+                    .map(|i| {
+                        (
+                            (row as isize + i * dr) as usize,
+                            (col as isize + i * dc) as usize,
+                        )
+                    })
+                    .collect();
+                lines.push((direction, line));
             }
         }
-        false
+        lines
+    }
+
+    /// Returns the cells on the straight line from one `(row, col)` cell to another, both
+    /// included and in order from `from`, or None if the cells don't share a row, column or
+    /// diagonal or either one is off the board.
+    pub fn ray(&self, from: (usize, usize), to: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        let on_board = |(row, col): (usize, usize)| row < self.rows && col < self.cols;
+        if !on_board(from) || !on_board(to) {
+            return None;
+        }
+        let dr = to.0 as isize - from.0 as isize;
+        let dc = to.1 as isize - from.1 as isize;
+        if dr != 0 && dc != 0 && dr.abs() != dc.abs() {
+            return None;
+        }
+        let steps = dr.abs().max(dc.abs());
+        Some(
+            (0..=steps)
+                .map(|i| {
+                    (
+                        (from.0 as isize + i * dr.signum()) as usize,
+                        (from.1 as isize + i * dc.signum()) as usize,
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Counts the consecutive discs belonging to the player starting next to the given cell and
+    /// walking in the direction (dr, dc). The given cell itself isn't counted, so adding the
+    /// counts for a direction and its opposite plus one gives the length of the line through it.
+    pub fn connected_count(
+        &self,
+        row: usize,
+        col: usize,
+        dr: isize,
+        dc: isize,
+        player: Player,
+    ) -> usize {
+        let mut count = 0;
+        let mut r = row as isize + dr;
+        let mut c = col as isize + dc;
+        while self.in_bounds(r, c) && self.board[r as usize][c as usize] == Cell::Player(player) {
+            count += 1;
+            r += dr;
+            c += dc;
+        }
+        count
+    }
+
+    fn in_bounds(&self, row: isize, col: isize) -> bool {
+        row >= 0 && col >= 0 && (row as usize) < self.rows && (col as usize) < self.cols
     }
 }
 
@@ -259,20 +1085,691 @@ mod tests {
 
     }
 
+    #[test]
+    fn zero_and_one_based_moves_agree() {
+        let mut zero_based = Board::new(Player::Red);
+        let mut one_based = Board::new(Player::Red);
+
+        zero_based.game_move_zero_based(3).unwrap();
+        one_based.game_move_one_based(4).unwrap();
+
+        assert_eq!(zero_based.board, one_based.board);
+        assert_eq!(zero_based.column_height(3), 1);
+        for _ in 0..5 {
+            one_based.game_move_one_based(4).unwrap();
+        }
+        assert_eq!(
+            one_based.game_move_one_based(4).err(),
+            Some(MoveError::ColumnFull(4))
+        );
+        assert!(one_based.game_move_one_based(0).is_err());
+        assert!(one_based.game_move_one_based(8).is_err());
+        assert!(zero_based.game_move_zero_based(7).is_err());
+    }
+
+    #[test]
+    fn undo_restores_cell_and_player() {
+        let mut board = Board::new(Player::Red);
+
+        assert!(board.undo().is_none());
+
+        board.game_move(3).unwrap();
+        board.game_move(3).unwrap();
+        board.game_move(4).unwrap();
+
+        assert_eq!(board.peek_undo(), Some((0, 4, Player::Red)));
+        let record = board.undo().unwrap();
+        assert_eq!(
+            record,
+            MoveRecord {
+                col: 4,
+                row: 0,
+                player: Player::Red
+            }
+        );
+        assert_eq!(board.current_player, Player::Red);
+        assert_eq!(board.board[0][4], Cell::Empty);
+        assert_eq!(board.move_count, 2);
+
+        board.undo().unwrap();
+        board.undo().unwrap();
+        assert!(board.moves.is_empty());
+        assert_eq!(board.peek_undo(), None);
+        for row in board.board {
+            assert!(row.iter().eq([Cell::Empty; 7].iter()));
+        }
+    }
+
+    #[test]
+    fn history_pairs_players_with_columns() {
+        let mut board = Board::new(Player::Red);
+
+        board.game_move(3).unwrap();
+        board.game_move(2).unwrap();
+        board.game_move(3).unwrap();
+
+        let transcript: Vec<String> = board
+            .history()
+            .map(|(player, col)| format!("{:?}: {}", player, col + 1))
+            .collect();
+
+        assert_eq!(transcript.join(", "), "Red: 4, Yellow: 3, Red: 4");
+    }
+
+    #[test]
+    fn find_winner_scans_whole_board() {
+        let mut board = Board::new(Player::Red);
+        assert_eq!(board.find_winner(), None);
+
+        board.force_place(0, 6, Player::Yellow);
+        board.force_place(1, 5, Player::Yellow);
+        board.force_place(2, 4, Player::Yellow);
+        assert_eq!(board.find_winner(), None);
+
+        // A clone shares the cached windows and still sees its own discs
+        let mut finished = board.clone();
+        finished.force_place(3, 3, Player::Yellow);
+        assert_eq!(finished.find_winner(), Some(Player::Yellow));
+        assert_eq!(board.find_winner(), None);
+    }
+
+    #[test]
+    fn open_lines_drop_when_blocked() {
+        let mut board = Board::new(Player::Red);
+        assert_eq!(board.open_lines(Player::Red), 69);
+
+        // The corner cell sits on one horizontal, one vertical and one diagonal window
+        board.force_place(0, 0, Player::Yellow);
+        assert_eq!(board.open_lines(Player::Red), 66);
+        assert_eq!(board.open_lines(Player::Yellow), 69);
+    }
+
+    #[test]
+    fn connected_count_walks_each_direction() {
+        let mut board = Board::new(Player::Red);
+
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+        board.force_place(1, 0, Player::Red);
+        board.force_place(2, 0, Player::Yellow);
+        board.force_place(1, 1, Player::Red);
+        board.force_place(2, 2, Player::Red);
+
+        assert_eq!(board.connected_count(0, 0, 0, 1, Player::Red), 2);
+        assert_eq!(board.connected_count(0, 0, 1, 0, Player::Red), 1);
+        assert_eq!(board.connected_count(0, 0, 1, 0, Player::Yellow), 0);
+        assert_eq!(board.connected_count(0, 0, 1, 1, Player::Red), 2);
+        assert_eq!(board.connected_count(2, 0, -1, 1, Player::Red), 2);
+        assert_eq!(board.connected_count(0, 0, 0, -1, Player::Red), 0);
+    }
+
+    #[test]
+    fn ray_follows_aligned_cells() {
+        let board = Board::new(Player::Red);
+
+        assert_eq!(
+            board.ray((0, 3), (3, 0)),
+            Some(vec![(0, 3), (1, 2), (2, 1), (3, 0)])
+        );
+        assert_eq!(
+            board.ray((2, 5), (2, 3)),
+            Some(vec![(2, 5), (2, 4), (2, 3)])
+        );
+        assert_eq!(board.ray((1, 1), (1, 1)), Some(vec![(1, 1)]));
+        assert_eq!(board.ray((0, 0), (1, 2)), None);
+        assert_eq!(board.ray((0, 0), (6, 0)), None);
+    }
+
+    #[test]
+    fn has_floating_discs_finds_gap() {
+        let mut board = Board::new(Player::Red);
+        board.game_move(1).unwrap();
+        board.game_move(1).unwrap();
+        assert_eq!(board.has_floating_discs(), None);
+
+        board.force_place(3, 4, Player::Red);
+        board.force_place(2, 5, Player::Yellow);
+        assert_eq!(board.has_floating_discs(), Some((3, 4)));
+    }
+
+    #[test]
+    fn swap_sides_under_pie_rule() {
+        let mut board = Board::new(Player::Red);
+        board.names = Some(("Alice".to_string(), "Bob".to_string()));
+        board.game_move(3).unwrap();
+        assert!(!board.swap_sides());
+
+        board.undo();
+        board.swap_rule = true;
+        assert!(!board.can_swap());
+        board.game_move(3).unwrap();
+        assert!(board.swap_sides());
+        assert!(board.swapped());
+        assert!(!board.can_swap());
+
+        // Alice opened as Red and now plays Yellow, which is the colour to move
+        assert_eq!(board.current_player, Player::Yellow);
+        assert_eq!(board.player_name(Player::Yellow), "Alice");
+        assert_eq!(board.player_name(Player::Red), "Bob");
+
+        board.undo();
+        assert!(!board.swapped());
+        assert_eq!(board.player_name(Player::Red), "Alice");
+    }
+
+    #[test]
+    fn new_with_setup_drops_handicap() {
+        let setup = [(3, Player::Red), (3, Player::Red), (2, Player::Red)];
+        let mut board = Board::new_with_setup(Player::Yellow, &setup).unwrap();
+        board.print();
+
+        assert_eq!(board.board[1][3], Cell::Player(Player::Red));
+        assert_eq!(board.board[0][2], Cell::Player(Player::Red));
+        assert_eq!(board.current_player, Player::Yellow);
+        assert_eq!(board.move_count, 3);
+        assert!(board.undo().is_none());
+
+        assert_eq!(
+            Board::new_with_setup(Player::Red, &[(4, Player::Red); 7]).err(),
+            Some(MoveError::ColumnFull(4))
+        );
+        assert_eq!(
+            Board::new_with_setup(Player::Red, &[(7, Player::Red)]).err(),
+            Some(MoveError::InvalidColumn(7))
+        );
+    }
+
+    #[test]
+    fn new_with_setup_settles_a_connect_four() {
+        let setup: Vec<_> = (0..4).map(|col| (col, Player::Yellow)).collect();
+        let mut board = Board::new_with_setup(Player::Red, &setup).unwrap();
+
+        assert_eq!(board.status(), GameStatus::Won(Player::Yellow));
+        assert_eq!(board.game_move(5).err(), Some(MoveError::GameOver));
+    }
+
+    #[test]
+    fn is_terminal_after_win_or_full_board() {
+        let mut board = Board::new(Player::Red);
+        assert!(!board.is_terminal());
+
+        let mut won = board.clone();
+        for _ in 0..3 {
+            won.game_move(0).unwrap();
+            won.game_move(1).unwrap();
+        }
+        won.game_move(0).unwrap();
+        assert!(won.is_terminal());
+
+        for row in 0..4 {
+            board.update_cell(row, 5, Cell::Player(Player::Yellow));
+        }
+        assert!(board.is_terminal());
+
+        let mut full = Board::new(Player::Red);
+        for row in 0..6 {
+            for col in 0..7 {
+                let player = if (row / 2 + col) % 2 == 0 {
+                    Player::Red
+                } else {
+                    Player::Yellow
+                };
+                full.update_cell(row, col, Cell::Player(player));
+            }
+        }
+        assert!(full.is_full());
+        assert_eq!(full.find_winner(), None);
+        assert!(full.is_terminal());
+    }
+
+    #[test]
+    fn dimensions_follow_the_builder() {
+        let board = Board::new(Player::Red);
+        assert_eq!(board.dimensions(), (6, 7));
+        assert_eq!(board.win_length(), 4);
+
+        let board = BoardBuilder::new()
+            .rows(8)
+            .cols(9)
+            .win_length(5)
+            .build()
+            .unwrap();
+        assert_eq!(board.dimensions(), (8, 9));
+        assert_eq!(board.win_length(), 5);
+    }
+
+    #[test]
+    fn fullness_counts_occupied_cells() {
+        let mut board = Board::new(Player::Red);
+        assert_eq!(board.fullness(), 0.0);
+
+        for col in [0, 2, 4, 1, 3, 5, 6].iter().cycle().take(21) {
+            board.game_move(*col).unwrap();
+        }
+        assert_eq!(board.fullness(), 0.5);
+    }
+
+    #[test]
+    fn placed_ply_tracks_drops_and_undo() {
+        let mut board = Board::new(Player::Red);
+        for col in [3, 3, 2] {
+            board.game_move(col).unwrap();
+        }
+        board.force_place(0, 6, Player::Yellow);
+
+        assert_eq!(board.placed_ply(0, 3), Some(1));
+        assert_eq!(board.placed_ply(1, 3), Some(2));
+        assert_eq!(board.placed_ply(0, 2), Some(3));
+        assert_eq!(board.placed_ply(0, 6), None);
+
+        board.undo();
+        assert_eq!(board.placed_ply(0, 2), None);
+        board.game_move(4).unwrap();
+        assert_eq!(board.placed_ply(0, 4), Some(3));
+    }
+
+    #[test]
+    fn play_continues_after_win_without_stop_on_win() {
+        let mut board = Board::new(Player::Red);
+        board.stop_on_win = false;
+        for _ in 0..3 {
+            board.game_move(0).unwrap();
+            board.game_move(1).unwrap();
+        }
+
+        assert!(matches!(board.game_move(0), Ok(GameMoveResult::Valid)));
+        assert_eq!(board.status(), GameStatus::InProgress);
+        assert_eq!(board.current_player, Player::Yellow);
+        assert_eq!(
+            board.wins(),
+            &[(Player::Red, vec![(0, 0), (1, 0), (2, 0), (3, 0)])]
+        );
+
+        board.game_move(1).unwrap();
+        assert_eq!(board.wins().len(), 2);
+        assert_eq!(board.wins()[1].0, Player::Yellow);
+
+        board.undo();
+        board.undo();
+        assert!(board.wins().is_empty());
+    }
+
+    #[test]
+    fn compact_gravity_closes_gaps() {
+        let mut board = Board::new(Player::Red);
+        board.game_move(1).unwrap();
+        board.force_place(2, 4, Player::Red);
+        board.force_place(4, 4, Player::Yellow);
+        board.force_place(5, 4, Player::Red);
+        assert_eq!(board.has_floating_discs(), Some((2, 4)));
+
+        board.compact_gravity();
+        assert_eq!(board.has_floating_discs(), None);
+        assert_eq!(board.board[0][1], Cell::Player(Player::Red));
+        assert_eq!(board.board[0][4], Cell::Player(Player::Red));
+        assert_eq!(board.board[1][4], Cell::Player(Player::Yellow));
+        assert_eq!(board.board[2][4], Cell::Player(Player::Red));
+        assert_eq!(board.column_height(4), 3);
+        assert_eq!(board.legal_moves(), vec![0, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn two_passes_in_a_row_draw() {
+        let mut board = Board::new(Player::Red);
+        assert_eq!(board.pass(), Err(MoveError::PassNotAllowed));
+
+        board.allow_pass = true;
+        assert_eq!(board.pass(), Ok(GameStatus::InProgress));
+        assert_eq!(board.current_player, Player::Yellow);
+        board.game_move(3).unwrap();
+        assert_eq!(board.pass(), Ok(GameStatus::InProgress));
+        assert_eq!(board.pass(), Ok(GameStatus::Stalemate));
+        assert_eq!(board.pass_count(), 3);
+        assert_eq!(board.pass(), Err(MoveError::GameOver));
+    }
+
+    #[test]
+    fn repeated_positions_draw_on_third_time() {
+        let mut board = Board::new(Player::Red);
+        board.draw_on_repetition = true;
+        board.game_move(0).unwrap();
+        assert!(!board.position_repeated());
+
+        // Popping Yellow's disc back out and replaying it reaches the same position again
+        board.game_move(3).unwrap();
+        board.update_cell(0, 3, Cell::Empty);
+        board.current_player = Player::Yellow;
+        board.game_move(3).unwrap();
+        assert!(board.position_repeated());
+
+        board.update_cell(0, 3, Cell::Empty);
+        board.current_player = Player::Yellow;
+        assert!(matches!(board.game_move(3), Ok(GameMoveResult::Stalemate)));
+        assert_eq!(board.status(), GameStatus::Stalemate);
+
+        board.undo();
+        assert_eq!(board.status(), GameStatus::InProgress);
+        assert!(!Board::new(Player::Red).position_repeated());
+    }
+
+    #[test]
+    fn player_name_prefers_names() {
+        let mut board = Board::new(Player::Red);
+        assert_eq!(board.player_name(Player::Yellow), "Yellow");
+
+        board.names = Some(("Alice".to_string(), "Bob".to_string()));
+        assert_eq!(board.player_name(Player::Red), "Alice");
+        assert_eq!(board.player_name(Player::Yellow), "Bob");
+    }
+
+    #[test]
+    fn eval_message_suggests_a_move() {
+        let mut board = Board::new(Player::Red);
+        board.force_place(0, 0, Player::Red);
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+        board.current_player = Player::Yellow;
+
+        let message = board.eval_message(&Messages::default());
+        assert!(message.starts_with("Yellow to move: -"));
+        assert!(message.ends_with("suggested move: column 4"));
+    }
+
+    #[test]
+    fn resign_hands_win_to_opponent() {
+        let mut board = Board::new(Player::Red);
+
+        board.game_move(3).unwrap();
+        assert_eq!(board.status(), GameStatus::InProgress);
+
+        assert_eq!(board.resign(), GameStatus::Won(Player::Red));
+        assert_eq!(board.status(), GameStatus::Won(Player::Red));
+    }
+
+    #[test]
+    fn reward_from_each_perspective() {
+        let mut board = Board::new(Player::Red);
+        assert_eq!(board.reward(Player::Red), 0.0);
+        assert_eq!(board.reward(Player::Yellow), 0.0);
+
+        board.resign();
+        assert_eq!(board.reward(Player::Red), -1.0);
+        assert_eq!(board.reward(Player::Yellow), 1.0);
+
+        let mut board = Board::new(Player::Red);
+        for col in [0, 2, 4, 1, 3, 5, 6].iter().cycle().take(42) {
+            board.game_move(*col).unwrap();
+        }
+        assert_eq!(board.reward(Player::Red), 0.0);
+        assert_eq!(board.reward(Player::Yellow), 0.0);
+    }
+
+    #[test]
+    fn status_tracks_wins_and_stalemates() {
+        let mut board = Board::new(Player::Red);
+        for _ in 0..3 {
+            board.game_move(0).unwrap();
+            board.game_move(1).unwrap();
+        }
+        board.game_move(0).unwrap();
+        assert_eq!(board.status(), GameStatus::Won(Player::Red));
+        assert_eq!(board.game_move(2).err(), Some(MoveError::GameOver));
+        assert_eq!(board.current_player, Player::Red);
+
+        board.undo();
+        assert_eq!(board.status(), GameStatus::InProgress);
+
+        // This order flips the colours on every row so nobody connects four
+        let mut board = Board::new(Player::Red);
+        for col in [0, 2, 4, 1, 3, 5, 6].iter().cycle().take(41) {
+            assert!(matches!(board.game_move(*col), Ok(GameMoveResult::Valid)));
+        }
+        assert!(matches!(board.game_move(6), Ok(GameMoveResult::Stalemate)));
+        board.print();
+        assert_eq!(board.status(), GameStatus::Stalemate);
+    }
+
+    #[test]
+    fn legal_moves_follow_moves_and_undo() {
+        let mut board = Board::new(Player::Red);
+        for _ in 0..6 {
+            board.game_move(2).unwrap();
+        }
+        assert_eq!(board.legal_moves(), vec![0, 1, 3, 4, 5, 6]);
+        assert_eq!(board.ordered_moves(), vec![3, 4, 1, 5, 0, 6]);
+        assert!(!board.can_play(2));
+        assert!(board.can_play(3));
+        assert!(!board.can_play(99));
+
+        board.undo();
+        assert_eq!(board.legal_moves(), vec![0, 1, 2, 3, 4, 5, 6]);
+
+        for row in 0..6 {
+            board.update_cell(row, 6, Cell::Player(Player::Yellow));
+        }
+        assert_eq!(board.legal_moves(), vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(board.legal_mask(), 0b0011_1111);
+
+        // Walking the set bits gives the same columns as legal_moves
+        board.undo();
+        let mut mask = board.legal_mask();
+        let mut cols = Vec::new();
+        while mask != 0 {
+            cols.push(mask.trailing_zeros() as usize);
+            mask &= mask - 1;
+        }
+        assert_eq!(cols, board.legal_moves());
+    }
+
+    #[test]
+    fn ply_tracking() {
+        let mut board = Board::new(Player::Yellow);
+
+        assert_eq!(board.next_ply(), 1);
+        assert!(board.ply_parity());
+
+        board.game_move(3).unwrap();
+        assert_eq!(board.next_ply(), 2);
+        assert!(!board.ply_parity());
+
+        board.game_move(3).unwrap();
+        assert_eq!(board.next_ply(), 3);
+        assert!(board.ply_parity());
+    }
+
+    #[test]
+    fn column_top_and_height() {
+        let mut board = Board::new(Player::Red);
+
+        board.game_move(2).unwrap();
+        board.game_move(2).unwrap();
+        board.game_move(4).unwrap();
+
+        assert_eq!(board.column_top(2), Some((1, Cell::Player(Player::Yellow))));
+        assert_eq!(board.column_top(4), Some((0, Cell::Player(Player::Red))));
+        assert_eq!(board.column_top(0), None);
+        assert_eq!(board.column_height(2), 2);
+        assert_eq!(board.column_height(0), 0);
+    }
+
+    #[test]
+    fn winning_moves_for_each_player() {
+        let mut board = Board::new(Player::Red);
+
+        board.force_place(0, 0, Player::Red);
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+        board.force_place(0, 6, Player::Yellow);
+        board.force_place(1, 6, Player::Yellow);
+        board.force_place(2, 6, Player::Yellow);
+
+        assert_eq!(board.winning_moves(Player::Red), vec![3]);
+        assert_eq!(board.winning_moves(Player::Yellow), vec![6]);
+    }
+
+    #[test]
+    fn win_if_removed_drops_discs() {
+        let mut board = Board::new(Player::Red);
+
+        board.force_place(0, 0, Player::Red);
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+        board.force_place(0, 3, Player::Yellow);
+        board.force_place(1, 3, Player::Red);
+        board.force_place(0, 4, Player::Yellow);
+
+        assert_eq!(board.win_if_removed(0, 3), Some(Player::Red));
+        assert_eq!(board.win_if_removed(0, 4), None);
+        assert_eq!(board.win_if_removed(3, 3), None);
+        assert_eq!(board.board[0][3], Cell::Player(Player::Yellow));
+    }
+
+    #[test]
+    fn fall_distance_leaves_discs_floating() {
+        let mut board = Board::new(Player::Red);
+        board.set_fall_distance(Some(2));
+
+        board.game_move(0).unwrap();
+        assert_eq!(board.board[4][0], Cell::Player(Player::Red));
+        assert_eq!(board.board[0][0], Cell::Empty);
+
+        board.game_move(0).unwrap();
+        assert_eq!(board.board[5][0], Cell::Player(Player::Yellow));
+        assert!(board.game_move(0).is_err());
+
+        // A disc still rests on the stack when the stack is closer than the fall distance
+        board.force_place(0, 1, Player::Red);
+        board.force_place(1, 1, Player::Yellow);
+        board.force_place(2, 1, Player::Red);
+        board.force_place(3, 1, Player::Yellow);
+        board.force_place(4, 1, Player::Yellow);
+        board.game_move(1).unwrap();
+        assert_eq!(board.board[5][1], Cell::Player(Player::Red));
+
+        board.print();
+    }
+
+    #[test]
+    fn set_fall_distance_reopens_columns() {
+        let mut board = Board::new(Player::Red);
+        board.set_fall_distance(Some(2));
+        board.game_move(0).unwrap();
+        board.game_move(0).unwrap();
+        assert!(!board.can_play(0));
+
+        // With normal gravity the cells under the floating discs can be filled again
+        board.set_fall_distance(None);
+        assert_eq!(board.fall_distance(), None);
+        assert!(board.can_play(0));
+        assert_eq!(board.legal_moves(), (0..7).collect::<Vec<_>>());
+
+        board.set_fall_distance(Some(2));
+        assert!(!board.legal_moves().contains(&0));
+    }
+
+    #[test]
+    fn as_flat_is_row_major_from_bottom_left() {
+        let mut board = Board::new(Player::Red);
+
+        board.force_place(0, 0, Player::Red);
+        board.force_place(0, 6, Player::Yellow);
+        board.force_place(1, 0, Player::Yellow);
+        board.force_place(5, 6, Player::Red);
+
+        let flat = board.as_flat();
+        assert_eq!(flat.len(), 42);
+        assert_eq!(flat[0], Cell::Player(Player::Red));
+        assert_eq!(flat[6], Cell::Player(Player::Yellow));
+        assert_eq!(flat[7], Cell::Player(Player::Yellow));
+        assert_eq!(flat[41], Cell::Player(Player::Red));
+        assert_eq!(flat.iter().filter(|&&c| c == Cell::Empty).count(), 38);
+    }
+
+    #[test]
+    #[should_panic]
+    fn force_place_checks_bounds() {
+        let mut board = Board::new(Player::Red);
+        board.force_place(6, 0, Player::Red);
+    }
+
+    #[test]
+    fn win_details_reports_direction_and_line() {
+        let mut board = Board::new(Player::Red);
+
+        board.force_place(0, 2, Player::Yellow);
+        board.force_place(1, 2, Player::Yellow);
+        board.force_place(2, 2, Player::Yellow);
+        board.force_place(3, 2, Player::Yellow);
+
+        board.force_place(3, 3, Player::Red);
+        board.force_place(2, 4, Player::Red);
+        board.force_place(1, 5, Player::Red);
+        board.force_place(0, 6, Player::Red);
+
+        assert_eq!(
+            board.win_details(1, 2, Player::Yellow),
+            Some((WinDirection::Vertical, vec![(0, 2), (1, 2), (2, 2), (3, 2)]))
+        );
+        assert_eq!(
+            board.win_details(2, 4, Player::Red),
+            Some((
+                WinDirection::DiagonalDown,
+                vec![(3, 3), (2, 4), (1, 5), (0, 6)]
+            ))
+        );
+        assert_eq!(board.win_details(2, 4, Player::Yellow), None);
+        assert_eq!(board.win_details(5, 5, Player::Red), None);
+    }
+
+    #[test]
+    fn double_win_reports_both_lines() {
+        let mut board = Board::new(Player::Red);
+        board.stop_on_win = false;
+        board.report_all_lines = true;
+
+        // Column 3 finishes Red's bottom row and the diagonal rising from the same cell
+        board.force_place(0, 0, Player::Red);
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+        board.force_place(0, 4, Player::Yellow);
+        board.force_place(1, 4, Player::Red);
+        board.force_place(2, 5, Player::Red);
+        board.force_place(3, 6, Player::Red);
+        board.print();
+
+        board.game_move(3).unwrap();
+        assert_eq!(
+            board.all_win_details(0, 3, Player::Red),
+            vec![
+                (WinDirection::Horizontal, vec![(0, 0), (0, 1), (0, 2), (0, 3)]),
+                (WinDirection::DiagonalUp, vec![(0, 3), (1, 4), (2, 5), (3, 6)]),
+            ]
+        );
+        assert_eq!(board.wins().len(), 2);
+        assert_eq!(
+            board.win_details(0, 3, Player::Red).map(|(direction, _)| direction),
+            Some(WinDirection::Horizontal)
+        );
+
+        board.undo();
+        assert!(board.wins().is_empty());
+        assert!(board.all_win_details(0, 3, Player::Red).is_empty());
+    }
+
     #[test]
     fn horizontal_connect_four() {
         let mut board = Board::new(Player::Red);
 
-        board.update_cell(0, 1, Cell::Player(Player::Red));
-        board.update_cell(0, 2, Cell::Player(Player::Yellow));
-        board.update_cell(0, 3, Cell::Player(Player::Red));
-        board.update_cell(0, 4, Cell::Player(Player::Red));
-        board.update_cell(0, 5, Cell::Player(Player::Red));
-        board.update_cell(0, 6, Cell::Player(Player::Red));
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Yellow);
+        board.force_place(0, 3, Player::Red);
+        board.force_place(0, 4, Player::Red);
+        board.force_place(0, 5, Player::Red);
+        board.force_place(0, 6, Player::Red);
 
-        board.update_cell(1, 1, Cell::Player(Player::Red));
-        board.update_cell(1, 2, Cell::Player(Player::Red));
-        board.update_cell(1, 3, Cell::Player(Player::Red));
+        board.force_place(1, 1, Player::Red);
+        board.force_place(1, 2, Player::Red);
+        board.force_place(1, 3, Player::Red);
 
         board.print();
         assert!(board.has_won(0, 3));
@@ -283,12 +1780,12 @@ mod tests {
     fn vertical_connect_four() {
         let mut board = Board::new(Player::Yellow);
 
-        board.update_cell(0, 3, Cell::Player(Player::Yellow));
-        board.update_cell(1, 3, Cell::Player(Player::Red));
-        board.update_cell(2, 3, Cell::Player(Player::Yellow));
-        board.update_cell(3, 3, Cell::Player(Player::Yellow));
-        board.update_cell(4, 3, Cell::Player(Player::Yellow));
-        board.update_cell(5, 3, Cell::Player(Player::Yellow));
+        board.force_place(0, 3, Player::Yellow);
+        board.force_place(1, 3, Player::Red);
+        board.force_place(2, 3, Player::Yellow);
+        board.force_place(3, 3, Player::Yellow);
+        board.force_place(4, 3, Player::Yellow);
+        board.force_place(5, 3, Player::Yellow);
 
         board.print();
 
@@ -300,19 +1797,19 @@ mod tests {
         let mut board = Board::new(Player::Yellow);
 
         // Left diagonal
-        board.update_cell(0, 3, Cell::Player(Player::Yellow));
-        board.update_cell(1, 3, Cell::Player(Player::Red));
-        board.update_cell(2, 4, Cell::Player(Player::Red));
-        board.update_cell(3, 5, Cell::Player(Player::Red));
-        board.update_cell(4, 6, Cell::Player(Player::Red));
+        board.force_place(0, 3, Player::Yellow);
+        board.force_place(1, 3, Player::Red);
+        board.force_place(2, 4, Player::Red);
+        board.force_place(3, 5, Player::Red);
+        board.force_place(4, 6, Player::Red);
 
         // Right diagonal
-        board.update_cell(3, 3, Cell::Player(Player::Red));
-        board.update_cell(4, 2, Cell::Player(Player::Red));
-        board.update_cell(5, 1, Cell::Player(Player::Red));
+        board.force_place(3, 3, Player::Red);
+        board.force_place(4, 2, Player::Red);
+        board.force_place(5, 1, Player::Red);
 
         // 2 long diagonal
-        board.update_cell(5, 5, Cell::Player(Player::Red));
+        board.force_place(5, 5, Player::Red);
 
         board.print();
 
@@ -321,5 +1818,28 @@ mod tests {
         assert!(!board.has_won(5, 5));
     }
 
+    #[test]
+    fn south_east_connect_four_at_right_wall() {
+        // Each diagonal descends to the right and finishes on the last column
+        let diagonals = [
+            [(5, 3), (4, 4), (3, 5), (2, 6)],
+            [(4, 3), (3, 4), (2, 5), (1, 6)],
+            [(3, 3), (2, 4), (1, 5), (0, 6)],
+        ];
+
+        for diagonal in diagonals {
+            let mut board = Board::new(Player::Red);
+            for (row, col) in diagonal {
+                board.force_place(row, col, Player::Yellow);
+            }
+
+            board.print();
+
+            for (row, col) in diagonal {
+                assert!(board.has_won(row, col));
+            }
+        }
+    }
+
 
 }
\ No newline at end of file
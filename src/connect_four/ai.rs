@@ -0,0 +1,504 @@
+use super::*;
+
+/// The deepest search the AI will run. Requested depths above this are clamped, which keeps the
+/// recursion shallow and the search time bounded.
+pub const MAX_DEPTH: u32 = 10;
+
+/// Search depth the interactive game and the analysis console use when none is given.
+pub const DEFAULT_DEPTH: u32 = 6;
+
+/// Score given to a connect four, large enough to outweigh any heuristic score.
+const WIN_SCORE: i32 = 1_000_000;
+
+/// The scores `evaluate` adds up. `two` and `three` score open lines two and one discs short
+/// of a win, so on boards with a longer win length they still mean the last steps before it.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct EvalWeights {
+    /// Score for each disc in the centre column.
+    pub center: i32,
+    pub two: i32,
+    pub three: i32,
+    /// Score for a completed line.
+    pub win: i32,
+}
+
+impl EvalWeights {
+    pub fn new(center: i32, two: i32, three: i32, win: i32) -> EvalWeights {
+        EvalWeights {
+            center,
+            two,
+            three,
+            win,
+        }
+    }
+}
+
+impl Default for EvalWeights {
+    fn default() -> EvalWeights {
+        EvalWeights::new(3, 2, 5, WIN_SCORE)
+    }
+}
+
+/// Evaluation score that `win_probability` reads as roughly 73%, the value of a single open
+/// three. Three times it, about 95%, is what a row of three open at both ends scores, which is
+/// already a forced win. A connect four saturates to 1.
+pub const PROBABILITY_SCALE: f64 = 5.0;
+
+/// Largest `evaluate` lead that `advantage` still reads as even, worth one open three with
+/// the default weights.
+pub const EVEN_MARGIN: i32 = 5;
+
+/// Which player the heuristic favours, as reported by `advantage`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Advantage {
+    Red,
+    Yellow,
+    Even,
+}
+
+/// Evaluates every board from the given player's point of view, returning the scores in the
+/// same order. With the `rayon` feature the boards are scored in parallel.
+pub fn evaluate_batch(boards: &[Board], player: Player) -> Vec<i32> {
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        boards
+            .par_iter()
+            .map(|board| board.evaluate(player))
+            .collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        boards.iter().map(|board| board.evaluate(player)).collect()
+    }
+}
+
+impl Board {
+    /// Heuristic score of the position from the given player's point of view. Every line of
+    /// four that is still open for a player scores by how many of their discs it already holds,
+    /// and discs in the centre column get a bonus, both weighted by `eval_weights`. The
+    /// opponent's lines count against the score.
+    pub fn evaluate(&self, player: Player) -> i32 {
+        let weights = self.eval_weights;
+        let mut score = 0;
+
+        let centre = self.cols / 2;
+        for row in 0..self.rows {
+            match self.board[row][centre] {
+                Cell::Player(p) if p == player => score += weights.center,
+                Cell::Player(_) => score -= weights.center,
+                Cell::Empty => (),
+            }
+        }
+
+        for window in self.windows() {
+            let mut own = 0;
+            let mut other = 0;
+            for &(row, col) in window {
+                match self.board[row][col] {
+                    Cell::Player(p) if p == player => own += 1,
+                    Cell::Player(_) => other += 1,
+                    Cell::Empty => (),
+                }
+            }
+            if other == 0 {
+                score += self.window_score(own, &weights);
+            } else if own == 0 {
+                score -= self.window_score(other, &weights);
+            }
+        }
+        score
+    }
+
+    /// Maps `evaluate` for the player onto an estimated chance of winning between 0 and 1 with
+    /// a logistic curve scaled by `PROBABILITY_SCALE`. Even positions read 0.5.
+    pub fn win_probability(&self, player: Player) -> f64 {
+        let score = self.evaluate(player) as f64;
+        1.0 / (1.0 + (-score / PROBABILITY_SCALE).exp())
+    }
+
+    /// Returns the player `evaluate` favours, without any search, or `Advantage::Even` when
+    /// their lead is no more than `EVEN_MARGIN`. Cheap enough to refresh after every move.
+    pub fn advantage(&self) -> Advantage {
+        match self.evaluate(Player::Red) {
+            score if score > EVEN_MARGIN => Advantage::Red,
+            score if score < -EVEN_MARGIN => Advantage::Yellow,
+            _ => Advantage::Even,
+        }
+    }
+
+    /// Returns the best column for the current player found by searching `depth` plies ahead,
+    /// or None if there are no legal moves. Depths are clamped to `1..=MAX_DEPTH`. When several
+    /// columns score the same the one closest to the centre wins, then the lower index, so the
+    /// choice only depends on the position.
+    pub fn best_move(&self, depth: u32) -> Option<usize> {
+        self.principal_variation(depth).first().copied()
+    }
+
+    /// Returns the column the opponent would pick with `best_move` if it were their turn now,
+    /// searching `depth` plies ahead, to warn the current player about their strongest threat.
+    pub fn opponent_best_move(&self, depth: u32) -> Option<usize> {
+        let mut board = self.clone();
+        board.current_player = self.current_player.opponent();
+        board.best_move(depth)
+    }
+
+    /// Returns up to `n` legal columns with their search scores for the current player, best
+    /// first, searching `depth` plies ahead like `best_move`. Every column is searched with a
+    /// full window so each score is exact rather than a bound. Equal scores keep the centre
+    /// first order of `ordered_moves`.
+    pub fn ranked_moves(&self, depth: u32, n: usize) -> Vec<(usize, i32)> {
+        let depth = depth.clamp(1, MAX_DEPTH);
+        let mut line = Vec::new();
+        let mut ranked: Vec<(usize, i32)> = self
+            .ordered_moves()
+            .into_iter()
+            .map(|col| {
+                (
+                    col,
+                    self.score_move(col, depth, -i32::MAX, i32::MAX, &mut line),
+                )
+            })
+            .collect();
+        ranked.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        ranked.truncate(n);
+        ranked
+    }
+
+    /// Returns the legal columns scored by `evaluate` for the current player after dropping
+    /// their disc there, best first, without searching any replies. Much cheaper than
+    /// `ranked_moves` but blind to the opponent's answers. Equal scores keep the centre first
+    /// order of `ordered_moves`.
+    pub fn heuristic_ranking(&self) -> Vec<(usize, i32)> {
+        let player = self.current_player;
+        let mut ranked: Vec<(usize, i32)> = self
+            .ordered_moves()
+            .into_iter()
+            .map(|col| {
+                let mut board = self.clone();
+                let row = board.row_available(col).unwrap();
+                board.update_cell(row, col, Cell::Player(player));
+                (col, board.evaluate(player))
+            })
+            .collect();
+        ranked.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        ranked
+    }
+
+    /// Plays `best_move` for the current player and returns the result, so calling it in a loop
+    /// plays the AI against itself. Returns `MoveError::GameOver` once the game has ended.
+    pub fn ai_step(&mut self, depth: u32) -> Result<GameMoveResult, MoveError> {
+        if self.status() != GameStatus::InProgress {
+            return Err(MoveError::GameOver);
+        }
+        match self.best_move(depth) {
+            Some(col) => self.game_move(col),
+            None => Err(MoveError::GameOver),
+        }
+    }
+
+    /// Returns the line of play the search expects from here, starting with the current
+    /// player's best move and alternating between the players, searching `depth` plies ahead.
+    /// The line stops early if the game would end or a reply was pruned by the search. Returns
+    /// an empty line if there are no legal moves. Depths are clamped to `1..=MAX_DEPTH`.
+    pub fn principal_variation(&self, depth: u32) -> Vec<usize> {
+        let depth = depth.clamp(1, MAX_DEPTH);
+
+        let mut pv = Vec::new();
+        let mut line = Vec::new();
+        let mut alpha = -i32::MAX;
+        // Moves come centre first and only a strictly better score replaces the best so far,
+        // which gives the tie-break described on `best_move`
+        for col in self.ordered_moves() {
+            let score = self.score_move(col, depth, alpha, i32::MAX, &mut line);
+            if pv.is_empty() || score > alpha {
+                alpha = score;
+                std::mem::swap(&mut pv, &mut line);
+            }
+        }
+        pv
+    }
+
+    /// Counts the lines of play `depth` plies long from here, perft style, to check move
+    /// generation and measure how much work a full-width search would do. Games that end
+    /// sooner count once where they end.
+    pub fn count_nodes(&self, depth: u32) -> u64 {
+        if depth == 0 || self.status() != GameStatus::InProgress {
+            return 1;
+        }
+        self.legal_moves()
+            .into_iter()
+            .map(|col| {
+                let mut child = self.clone();
+                child.game_move(col).unwrap();
+                child.count_nodes(depth - 1)
+            })
+            .sum()
+    }
+
+    fn window_score(&self, discs: usize, weights: &EvalWeights) -> i32 {
+        if discs == self.win_length {
+            weights.win
+        } else if discs + 1 == self.win_length {
+            weights.three
+        } else if discs + 2 == self.win_length {
+            weights.two
+        } else {
+            0
+        }
+    }
+
+    /// Scores playing the given column for the current player, searching `depth` plies
+    /// including the move itself. The expected line starting with the move is written to `pv`.
+    fn score_move(
+        &self,
+        col: usize,
+        depth: u32,
+        alpha: i32,
+        beta: i32,
+        pv: &mut Vec<usize>,
+    ) -> i32 {
+        pv.clear();
+        pv.push(col);
+        let mut child = self.clone();
+        match child.game_move(col) {
+            // Prefer wins found closer to the root
+            Ok(GameMoveResult::Won(_)) => WIN_SCORE + depth as i32,
+            Ok(GameMoveResult::Valid) => {
+                let mut line = Vec::new();
+                let score = -child.search(depth - 1, -beta, -alpha, &mut line);
+                pv.extend(line);
+                score
+            }
+            _ => 0,
+        }
+    }
+
+    fn search(&self, depth: u32, mut alpha: i32, beta: i32, pv: &mut Vec<usize>) -> i32 {
+        if depth == 0 {
+            return self.evaluate(self.current_player);
+        }
+
+        let mut best = -i32::MAX;
+        let mut line = Vec::new();
+        for col in self.ordered_moves() {
+            let score = self.score_move(col, depth, alpha, beta, &mut line);
+            if score > best {
+                best = score;
+                std::mem::swap(pv, &mut line);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluate_is_symmetric_on_empty_board() {
+        let board = Board::new(Player::Red);
+
+        assert_eq!(board.evaluate(Player::Red), 0);
+        assert_eq!(board.evaluate(Player::Yellow), 0);
+    }
+
+    #[test]
+    fn evaluate_uses_custom_weights() {
+        let mut board = Board::new(Player::Red);
+        board.force_place(0, 3, Player::Red);
+        let default = board.evaluate(Player::Red);
+
+        board.eval_weights = EvalWeights::new(100, 2, 5, 1000);
+        assert_eq!(board.evaluate(Player::Red), default - 3 + 100);
+
+        board.eval_weights = EvalWeights {
+            center: 0,
+            two: 0,
+            ..EvalWeights::default()
+        };
+        assert_eq!(board.evaluate(Player::Red), 0);
+    }
+
+    #[test]
+    fn win_probability_is_even_then_favours_leader() {
+        let mut board = Board::new(Player::Red);
+        assert_eq!(board.win_probability(Player::Red), 0.5);
+
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+        board.force_place(0, 3, Player::Red);
+        assert!(board.win_probability(Player::Red) > 0.9);
+        assert!(board.win_probability(Player::Yellow) < 0.1);
+
+        board.force_place(0, 4, Player::Red);
+        assert!(board.win_probability(Player::Red) > 0.999);
+    }
+
+    #[test]
+    fn advantage_ignores_small_leads() {
+        let mut board = Board::new(Player::Red);
+        assert_eq!(board.advantage(), Advantage::Even);
+
+        board.game_move(3).unwrap();
+        assert_eq!(board.advantage(), Advantage::Even);
+
+        board.force_place(0, 0, Player::Yellow);
+        board.force_place(0, 1, Player::Yellow);
+        board.force_place(0, 2, Player::Yellow);
+        board.force_place(1, 0, Player::Yellow);
+        board.force_place(1, 1, Player::Yellow);
+        board.force_place(1, 2, Player::Yellow);
+        assert_eq!(board.advantage(), Advantage::Yellow);
+    }
+
+    #[test]
+    fn evaluate_batch_matches_single_evaluations() {
+        let mut boards = vec![Board::new(Player::Red)];
+        for col in [3, 2, 3] {
+            let mut next = boards.last().unwrap().clone();
+            next.game_move(col).unwrap();
+            boards.push(next);
+        }
+
+        let scores = evaluate_batch(&boards, Player::Red);
+
+        assert_eq!(scores.len(), boards.len());
+        for (board, score) in boards.iter().zip(scores) {
+            assert_eq!(board.evaluate(Player::Red), score);
+        }
+    }
+
+    #[test]
+    fn best_move_takes_win_and_blocks_loss() {
+        let mut board = Board::new(Player::Red);
+
+        board.force_place(0, 0, Player::Yellow);
+        board.force_place(0, 1, Player::Yellow);
+        board.force_place(0, 2, Player::Yellow);
+        assert_eq!(board.best_move(4), Some(3));
+
+        board.force_place(0, 6, Player::Red);
+        board.force_place(1, 6, Player::Red);
+        board.force_place(2, 6, Player::Red);
+        assert_eq!(board.best_move(4), Some(6));
+    }
+
+    #[test]
+    fn opponent_best_move_points_at_threat() {
+        let mut board = Board::new(Player::Red);
+
+        board.force_place(0, 4, Player::Yellow);
+        board.force_place(1, 4, Player::Yellow);
+        board.force_place(2, 4, Player::Yellow);
+        board.force_place(0, 0, Player::Red);
+
+        assert_eq!(board.opponent_best_move(3), Some(4));
+        assert_eq!(board.current_player, Player::Red);
+    }
+
+    #[test]
+    fn ranked_moves_put_the_win_first() {
+        let mut board = Board::new(Player::Red);
+        board.force_place(0, 0, Player::Red);
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+
+        let ranked = board.ranked_moves(2, 3);
+        assert_eq!(ranked.len(), 3);
+        assert_eq!(ranked[0].0, 3);
+        assert_eq!(Some(ranked[0].0), board.best_move(2));
+        assert!(ranked.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        assert_eq!(board.ranked_moves(2, 10).len(), 7);
+    }
+
+    #[test]
+    fn heuristic_ranking_prefers_centre_and_wins() {
+        let mut board = Board::new(Player::Red);
+        let ranked = board.heuristic_ranking();
+        assert_eq!(ranked.len(), 7);
+        assert_eq!(ranked[0].0, 3);
+
+        board.force_place(0, 0, Player::Red);
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+        assert_eq!(board.heuristic_ranking()[0].0, 3);
+        assert!(board.heuristic_ranking()[0].1 >= WIN_SCORE);
+    }
+
+    #[test]
+    fn best_move_clamps_deep_requests() {
+        let board = Board::new(Player::Red);
+
+        assert_eq!(board.best_move(100), board.best_move(MAX_DEPTH));
+    }
+
+    #[test]
+    fn principal_variation_is_playable() {
+        let mut board = Board::new(Player::Red);
+
+        board.force_place(0, 0, Player::Yellow);
+        board.force_place(0, 1, Player::Yellow);
+        board.force_place(0, 2, Player::Yellow);
+
+        let pv = board.principal_variation(4);
+        assert_eq!(pv[0], 3);
+        assert_eq!(pv.len(), 4);
+
+        let mut line = board.clone();
+        for col in pv {
+            assert!(matches!(line.game_move(col), Ok(GameMoveResult::Valid)));
+        }
+
+        board.force_place(0, 6, Player::Red);
+        board.force_place(1, 6, Player::Red);
+        board.force_place(2, 6, Player::Red);
+        assert_eq!(board.principal_variation(4), vec![6]);
+    }
+
+    #[test]
+    fn best_move_breaks_ties_towards_centre() {
+        let mut board = Board::new(Player::Red);
+        assert_eq!(board.best_move(4), Some(3));
+
+        // With the centre column full, columns 2 and 4 mirror each other
+        for row in 0..6 {
+            let player = if row % 2 == 0 {
+                Player::Red
+            } else {
+                Player::Yellow
+            };
+            board.force_place(row, 3, player);
+        }
+        assert_eq!(board.best_move(4), Some(2));
+    }
+
+    #[test]
+    fn count_nodes_matches_perft() {
+        let board = Board::new(Player::Red);
+        assert_eq!(board.count_nodes(0), 1);
+        assert_eq!(board.count_nodes(1), 7);
+        assert_eq!(board.count_nodes(4), 2401);
+
+        // Red wins with column 3 straight away, so that line stops after one ply
+        let mut board = Board::new(Player::Red);
+        board.force_place(0, 0, Player::Red);
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+        assert_eq!(board.count_nodes(2), 6 * 7 + 1);
+    }
+
+    #[test]
+    fn ai_step_plays_a_full_game() {
+        let mut board = Board::new(Player::Red);
+        while let Ok(GameMoveResult::Valid) = board.ai_step(2) {}
+
+        assert_ne!(board.status(), GameStatus::InProgress);
+        assert_eq!(board.ai_step(2).err(), Some(MoveError::GameOver));
+    }
+}
@@ -0,0 +1,703 @@
+use super::*;
+
+/// A line one disc short of a connect four, as found by `open_threes`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct OpenThree {
+    /// The player's discs already in the line, in the order the line runs.
+    pub discs: Vec<(usize, usize)>,
+    /// The empty cell that would complete the line.
+    pub completion: (usize, usize),
+}
+
+/// Whether a row is odd or even, counting from 1 at the bottom.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Parity {
+    Odd,
+    Even,
+}
+
+impl Board {
+    /// Returns true if the player could still complete the given cells, meaning each one is
+    /// either empty or already holds one of their discs.
+    pub fn window_open_for(&self, window: &[(usize, usize)], player: Player) -> bool {
+        window.iter().all(|&(row, col)| match self.board[row][col] {
+            Cell::Player(p) => p == player,
+            Cell::Empty => true,
+        })
+    }
+
+    /// Scores every empty cell by how many of the lines of four through it are still open for
+    /// the given player, meaning they hold none of the opponent's discs. Occupied cells score 0.
+    /// The result is indexed as `[row][col]` like the board itself.
+    pub fn influence(&self, player: Player) -> Vec<Vec<i32>> {
+        let mut influence = vec![vec![0; self.cols]; self.rows];
+        for window in self.windows() {
+            if !self.window_open_for(window, player) {
+                continue;
+            }
+            for &(row, col) in window {
+                if self.board[row][col] == Cell::Empty {
+                    influence[row][col] += 1;
+                }
+            }
+        }
+        influence
+    }
+
+    /// Returns the columns where dropping a disc for the player doesn't win straight away but
+    /// gives them a winning move they didn't have before on their next turn.
+    pub fn threatening_moves(&self, player: Player) -> Vec<usize> {
+        let before = self.winning_moves(player);
+        self.legal_moves()
+            .into_iter()
+            .filter(|col| !before.contains(col))
+            .filter(|&col| {
+                let mut board = self.clone();
+                let row = board.row_available(col).unwrap();
+                board.update_cell(row, col, Cell::Player(player));
+                board
+                    .winning_moves(player)
+                    .iter()
+                    .any(|threat| !before.contains(threat))
+            })
+            .collect()
+    }
+
+    /// Returns every position one move away, as each legal column paired with the board after
+    /// playing it with `apply`, in column order. Empty once the game is over.
+    pub fn children(&self) -> Vec<(usize, Board)> {
+        if self.status() != GameStatus::InProgress {
+            return Vec::new();
+        }
+        self.legal_moves()
+            .into_iter()
+            .map(|col| (col, self.apply(col).unwrap()))
+            .collect()
+    }
+
+    /// Returns the columns the current player can play without the opponent winning on the very
+    /// next move. Moves that win immediately are always included. An empty list means every move
+    /// loses, for example against a double threat.
+    pub fn defensive_moves(&self) -> Vec<usize> {
+        self.legal_moves()
+            .into_iter()
+            .filter(|&col| {
+                let mut child = self.clone();
+                match child.game_move(col) {
+                    Ok(GameMoveResult::Valid) => {
+                        child.winning_moves(child.current_player).is_empty()
+                    }
+                    _ => true,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns true if dropping the player's disc into the column doesn't win straight away but
+    /// leaves them with two or more winning moves, more than the opponent can block in one turn.
+    pub fn creates_fork(&self, col: usize, player: Player) -> bool {
+        if col >= self.cols {
+            return false;
+        }
+        let row = match self.row_available(col) {
+            Some(row) => row,
+            None => return false,
+        };
+        if self.completes_line(row, col, player) {
+            return false;
+        }
+        let mut board = self.clone();
+        board.update_cell(row, col, Cell::Player(player));
+        board.winning_moves(player).len() >= 2
+    }
+
+    /// Returns the columns the current player can play without the opponent being able to win
+    /// or set up a fork with their reply. Moves that win immediately are always included.
+    pub fn safe_moves(&self) -> Vec<usize> {
+        self.legal_moves()
+            .into_iter()
+            .filter(|&col| {
+                let mut child = self.clone();
+                match child.game_move(col) {
+                    Ok(GameMoveResult::Valid) => {
+                        let opponent = child.current_player;
+                        child.winning_moves(opponent).is_empty()
+                            && !child
+                                .legal_moves()
+                                .into_iter()
+                                .any(|reply| child.creates_fork(reply, opponent))
+                    }
+                    _ => true,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the columns where the current player's drop lets the opponent win on their very
+    /// next move, whether on top of the new disc or anywhere else, so a UI can warn about them.
+    /// These are the legal moves `defensive_moves` leaves out.
+    pub fn losing_moves(&self) -> Vec<usize> {
+        let safe = self.defensive_moves();
+        self.legal_moves()
+            .into_iter()
+            .filter(|col| !safe.contains(col))
+            .collect()
+    }
+
+    /// Returns true if dropping a disc in the column for the current player would let the
+    /// opponent win by playing on top of it. Moves that win straight away are never traps.
+    pub fn is_trap_move(&self, col: usize) -> bool {
+        let mut child = self.clone();
+        match child.game_move(col) {
+            Ok(GameMoveResult::Valid) => matches!(child.game_move(col), Ok(GameMoveResult::Won(_))),
+            _ => false,
+        }
+    }
+
+    /// Returns the vertically stacked pairs of cells used by the claimeven rule. Each column is
+    /// split into pairs made of an odd row (1-indexed from the bottom) and the even row directly
+    /// above it, so with an even number of rows the pairs cover every cell. Pairs are listed
+    /// column by column from the bottom, as `(lower, upper)` coordinates.
+    pub fn square_pairs(&self) -> Vec<((usize, usize), (usize, usize))> {
+        (0..self.cols)
+            .flat_map(|col| {
+                (0..self.rows / 2).map(move |pair| ((2 * pair, col), (2 * pair + 1, col)))
+            })
+            .collect()
+    }
+
+    /// Returns the parity of the given zero-based row, counting rows from 1 at the bottom, so
+    /// the bottom row is odd.
+    pub fn square_parity(&self, row: usize) -> Parity {
+        if row.is_multiple_of(2) {
+            Parity::Odd
+        } else {
+            Parity::Even
+        }
+    }
+
+    /// Returns true if a threat on the given `(row, col)` cell has the parity that favours the
+    /// player: odd rows for the player who moved first and even rows for the other. When the
+    /// board fills up, the first player tends to get the odd cells and the second the even
+    /// ones. Who moved first is worked out from `ply_parity`.
+    pub fn favorable_parity(&self, player: Player, cell: (usize, usize)) -> bool {
+        let first = if self.ply_parity() {
+            self.current_player
+        } else {
+            self.current_player.opponent()
+        };
+        let wanted = if player == first {
+            Parity::Odd
+        } else {
+            Parity::Even
+        };
+        self.square_parity(cell.0) == wanted
+    }
+
+    /// Returns the number of empty cells left on the board.
+    pub fn moves_remaining(&self) -> usize {
+        self.as_flat()
+            .iter()
+            .filter(|&&cell| cell == Cell::Empty)
+            .count()
+    }
+
+    /// Returns the player who would drop the last disc if the board filled up without anyone
+    /// winning. With an odd number of cells left that is the current player, otherwise their
+    /// opponent, which is also who filled the last cell of a full board.
+    pub fn last_mover(&self) -> Player {
+        if self.moves_remaining() % 2 == 1 {
+            self.current_player
+        } else {
+            self.current_player.opponent()
+        }
+    }
+
+    /// Counts the cells that hold something different on the two boards, or returns None if
+    /// the boards aren't the same size. Whose turn it is doesn't count.
+    pub fn distance(&self, other: &Board) -> Option<usize> {
+        if (self.rows, self.cols) != (other.rows, other.cols) {
+            return None;
+        }
+        Some(
+            self.as_flat()
+                .iter()
+                .zip(other.as_flat())
+                .filter(|&(&a, b)| a != b)
+                .count(),
+        )
+    }
+
+    /// Returns the mean column index of the player's discs, or None if they have none on the
+    /// board. Values close to the centre column indicate central play.
+    pub fn average_column(&self, player: Player) -> Option<f64> {
+        let columns: Vec<usize> = self
+            .board
+            .iter()
+            .flat_map(|row| row.iter().enumerate())
+            .filter(|&(_, &cell)| cell == Cell::Player(player))
+            .map(|(col, _)| col)
+            .collect();
+
+        if columns.is_empty() {
+            return None;
+        }
+        Some(columns.iter().sum::<usize>() as f64 / columns.len() as f64)
+    }
+
+    /// Groups the player's discs into clusters of discs joined up, down, left or right, as
+    /// `(row, col)` cells. Clusters are ordered by their lowest, then leftmost, disc and the
+    /// cells of each are sorted the same way.
+    pub fn clusters(&self, player: Player) -> Vec<Vec<(usize, usize)>> {
+        let mut seen = vec![vec![false; self.cols]; self.rows];
+        let mut clusters = Vec::new();
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                if seen[row][col] || self.board[row][col] != Cell::Player(player) {
+                    continue;
+                }
+                seen[row][col] = true;
+                let mut cluster = Vec::new();
+                let mut stack = vec![(row, col)];
+                while let Some((r, c)) = stack.pop() {
+                    cluster.push((r, c));
+                    let neighbours = [
+                        (r.checked_sub(1), Some(c)),
+                        (Some(r + 1), Some(c)),
+                        (Some(r), c.checked_sub(1)),
+                        (Some(r), Some(c + 1)),
+                    ];
+                    for (nr, nc) in neighbours {
+                        let (nr, nc) = match (nr, nc) {
+                            (Some(nr), Some(nc)) if nr < self.rows && nc < self.cols => (nr, nc),
+                            _ => continue,
+                        };
+                        if !seen[nr][nc] && self.board[nr][nc] == Cell::Player(player) {
+                            seen[nr][nc] = true;
+                            stack.push((nr, nc));
+                        }
+                    }
+                }
+                cluster.sort_unstable();
+                clusters.push(cluster);
+            }
+        }
+        clusters
+    }
+
+    /// Returns Red's and Yellow's winning columns, in that order, like calling `winning_moves`
+    /// for each player. The landing cell of each column is worked out once and checked for both
+    /// players in place, without copying the board.
+    pub fn all_immediate_wins(&self) -> (Vec<usize>, Vec<usize>) {
+        let mut red = Vec::new();
+        let mut yellow = Vec::new();
+        for col in self.legal_moves() {
+            let row = self.row_available(col).unwrap();
+            if self.completes_line(row, col, Player::Red) {
+                red.push(col);
+            }
+            if self.completes_line(row, col, Player::Yellow) {
+                yellow.push(col);
+            }
+        }
+        (red, yellow)
+    }
+
+    /// Returns the landing cell, as `(row, col)`, of every column where a disc would connect
+    /// four right now, paired with the player it would win for. Columns are listed from left
+    /// to right, with Red before Yellow when a cell wins for both.
+    pub fn winning_cells(&self) -> Vec<((usize, usize), Player)> {
+        let mut cells = Vec::new();
+        for col in self.legal_moves() {
+            let row = self.row_available(col).unwrap();
+            for player in [Player::Red, Player::Yellow] {
+                if self.completes_line(row, col, player) {
+                    cells.push(((row, col), player));
+                }
+            }
+        }
+        cells
+    }
+
+    /// Returns how many windows of `win_length` cells dropping the player's disc into the column
+    /// would complete, or 0 if the column is full or invalid. A drop where lines cross counts
+    /// each line, and a line longer than `win_length` counts once for every window it covers.
+    pub fn wins_completed_by(&self, col: usize, player: Player) -> usize {
+        if col >= self.cols {
+            return 0;
+        }
+        let row = match self.row_available(col) {
+            Some(row) => row,
+            None => return 0,
+        };
+        self.windows()
+            .iter()
+            .filter(|window| window.contains(&(row, col)))
+            .filter(|window| {
+                window.iter().all(|&cell| {
+                    cell == (row, col) || self.board[cell.0][cell.1] == Cell::Player(player)
+                })
+            })
+            .count()
+    }
+
+    /// Returns every window of `win_length` cells holding all but one of the player's discs
+    /// and an empty cell, whether or not the empty cell can be played yet. A disc that would
+    /// complete two lines shows up once for each.
+    pub fn open_threes(&self, player: Player) -> Vec<OpenThree> {
+        self.windows()
+            .iter()
+            .filter(|window| self.window_open_for(window, player))
+            .filter_map(|window| {
+                let (empty, discs): (Vec<_>, Vec<_>) = window
+                    .iter()
+                    .partition(|&&(row, col)| self.board[row][col] == Cell::Empty);
+                match empty.as_slice() {
+                    &[completion] => Some(OpenThree { discs, completion }),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns true if a disc for the player in the given empty cell would connect
+    /// `win_length` in some direction.
+    pub(crate) fn completes_line(&self, row: usize, col: usize, player: Player) -> bool {
+        [(0, 1), (1, 0), (1, 1), (-1, 1)].iter().any(|&(dr, dc)| {
+            1 + self.connected_count(row, col, dr, dc, player)
+                + self.connected_count(row, col, -dr, -dc, player)
+                >= self.win_length
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_open_for_rejects_opponent_discs() {
+        let mut board = Board::new(Player::Red);
+        let window = [(0, 0), (0, 1), (0, 2), (0, 3)];
+
+        board.force_place(0, 1, Player::Red);
+        assert!(board.window_open_for(&window, Player::Red));
+
+        board.force_place(0, 3, Player::Yellow);
+        assert!(!board.window_open_for(&window, Player::Red));
+        assert!(!board.window_open_for(&window, Player::Yellow));
+        assert!(board.window_open_for(&[(0, 2), (0, 3)], Player::Yellow));
+    }
+
+    #[test]
+    fn influence_is_blocked_by_opponent_discs() {
+        let mut board = Board::new(Player::Red);
+
+        assert_eq!(
+            board.influence(Player::Red),
+            board.influence(Player::Yellow)
+        );
+        assert_eq!(board.influence(Player::Red)[0][0], 3);
+
+        board.force_place(0, 3, Player::Yellow);
+
+        let red = board.influence(Player::Red);
+        let yellow = board.influence(Player::Yellow);
+
+        assert_eq!(red[0][0], 2);
+        assert_eq!(yellow[0][0], 3);
+        assert_eq!(red[0][3], 0);
+        assert_eq!(yellow[0][3], 0);
+        assert!(red[1][3] < yellow[1][3]);
+    }
+
+    #[test]
+    fn parity_favours_first_player_on_odd_rows() {
+        let mut board = Board::new(Player::Yellow);
+        assert_eq!(board.square_parity(0), Parity::Odd);
+        assert_eq!(board.square_parity(3), Parity::Even);
+
+        board.game_move(3).unwrap();
+        assert!(board.favorable_parity(Player::Yellow, (0, 2)));
+        assert!(!board.favorable_parity(Player::Red, (0, 2)));
+        assert!(board.favorable_parity(Player::Red, (1, 2)));
+        assert!(board.favorable_parity(Player::Yellow, (4, 5)));
+    }
+
+    #[test]
+    fn last_mover_follows_tempo() {
+        let mut board = Board::new(Player::Red);
+        assert_eq!(board.moves_remaining(), 42);
+        assert_eq!(board.last_mover(), Player::Yellow);
+
+        board.game_move(3).unwrap();
+        assert_eq!(board.moves_remaining(), 41);
+        assert_eq!(board.last_mover(), Player::Yellow);
+
+        // A disc dropped out of turn changes who gets the last cell
+        board.force_place(0, 0, Player::Red);
+        assert_eq!(board.last_mover(), Player::Red);
+    }
+
+    #[test]
+    fn distance_counts_differing_cells() {
+        let mut board = Board::new(Player::Red);
+        let empty = board.clone();
+        assert_eq!(board.distance(&empty), Some(0));
+
+        board.game_move(3).unwrap();
+        board.game_move(4).unwrap();
+        let mut other = empty.clone();
+        other.game_move(3).unwrap();
+        other.game_move(2).unwrap();
+        assert_eq!(board.distance(&empty), Some(2));
+        assert_eq!(board.distance(&other), Some(2));
+
+        let small = BoardBuilder::new().rows(4).cols(4).build().unwrap();
+        assert_eq!(board.distance(&small), None);
+    }
+
+    #[test]
+    fn average_column_of_each_player() {
+        let mut board = Board::new(Player::Red);
+        assert_eq!(board.average_column(Player::Red), None);
+
+        board.game_move(3).unwrap();
+        board.game_move(0).unwrap();
+        board.game_move(4).unwrap();
+
+        assert_eq!(board.average_column(Player::Red), Some(3.5));
+        assert_eq!(board.average_column(Player::Yellow), Some(0.0));
+    }
+
+    #[test]
+    fn threatening_moves_extend_two_in_a_row() {
+        let mut board = Board::new(Player::Red);
+
+        board.force_place(0, 2, Player::Red);
+        board.force_place(0, 3, Player::Red);
+
+        assert_eq!(board.threatening_moves(Player::Red), vec![0, 1, 4, 5]);
+        assert!(board.threatening_moves(Player::Yellow).is_empty());
+
+        // Once col 4 already wins it no longer counts as a threat to set up
+        board.force_place(0, 1, Player::Red);
+        assert!(!board.threatening_moves(Player::Red).contains(&4));
+    }
+
+    #[test]
+    fn children_expand_two_plies() {
+        let board = Board::new(Player::Red);
+        let children = board.children();
+
+        assert_eq!(children.len(), 7);
+        for (col, child) in &children {
+            assert_eq!(child.board[0][*col], Cell::Player(Player::Red));
+            assert_eq!(child.current_player, Player::Yellow);
+        }
+        let grandchildren: usize = children
+            .iter()
+            .map(|(_, child)| child.children().len())
+            .sum();
+        assert_eq!(grandchildren, 49);
+        assert!(board.moves.is_empty());
+
+        let mut full = board.clone();
+        for _ in 0..6 {
+            full.game_move(0).unwrap();
+        }
+        assert_eq!(
+            full.children()
+                .iter()
+                .map(|(col, _)| *col)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5, 6]
+        );
+    }
+
+    #[test]
+    fn defensive_moves_block_single_threat() {
+        let mut board = Board::new(Player::Red);
+
+        board.force_place(0, 0, Player::Yellow);
+        board.force_place(0, 1, Player::Yellow);
+        board.force_place(0, 2, Player::Yellow);
+
+        assert_eq!(board.defensive_moves(), vec![3]);
+    }
+
+    #[test]
+    fn defensive_moves_empty_against_fork() {
+        let mut board = Board::new(Player::Red);
+
+        board.force_place(0, 2, Player::Yellow);
+        board.force_place(0, 3, Player::Yellow);
+        board.force_place(0, 4, Player::Yellow);
+        board.force_place(1, 3, Player::Red);
+
+        assert!(board.defensive_moves().is_empty());
+    }
+
+    #[test]
+    fn trap_move_under_opponent_threat() {
+        let mut board = Board::new(Player::Red);
+
+        board.force_place(1, 0, Player::Yellow);
+        board.force_place(1, 1, Player::Yellow);
+        board.force_place(1, 2, Player::Yellow);
+        board.force_place(0, 0, Player::Red);
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Yellow);
+
+        assert!(board.is_trap_move(3));
+        assert!(!board.is_trap_move(4));
+        assert_eq!(board.losing_moves(), vec![3]);
+
+        // With a second threat on the board every move but the block loses
+        board.force_place(0, 6, Player::Yellow);
+        board.force_place(1, 6, Player::Yellow);
+        board.force_place(2, 6, Player::Yellow);
+        assert_eq!(board.losing_moves(), vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn square_pairs_cover_the_board() {
+        let board = Board::new(Player::Red);
+        let pairs = board.square_pairs();
+
+        assert_eq!(pairs.len(), 21);
+        assert_eq!(pairs[0], ((0, 0), (1, 0)));
+        assert_eq!(pairs[2], ((4, 0), (5, 0)));
+        assert_eq!(pairs[3], ((0, 1), (1, 1)));
+
+        let mut covered: Vec<(usize, usize)> = pairs
+            .iter()
+            .flat_map(|&(lower, upper)| [lower, upper])
+            .collect();
+        covered.sort();
+        covered.dedup();
+        assert_eq!(covered.len(), 42);
+    }
+
+    #[test]
+    fn all_immediate_wins_for_both_players() {
+        let mut board = Board::new(Player::Red);
+
+        // Red has three along the bottom and Yellow three up the last column, plus three on the
+        // second row that can't be finished until a disc lands in column 3
+        board.force_place(0, 0, Player::Red);
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+        board.force_place(1, 0, Player::Yellow);
+        board.force_place(1, 1, Player::Yellow);
+        board.force_place(1, 2, Player::Yellow);
+        board.force_place(0, 6, Player::Yellow);
+        board.force_place(1, 6, Player::Yellow);
+        board.force_place(2, 6, Player::Yellow);
+
+        let (red, yellow) = board.all_immediate_wins();
+        assert_eq!(red, vec![3]);
+        assert_eq!(yellow, vec![6]);
+        assert_eq!(red, board.winning_moves(Player::Red));
+        assert_eq!(yellow, board.winning_moves(Player::Yellow));
+        assert_eq!(
+            board.winning_cells(),
+            vec![((0, 3), Player::Red), ((3, 6), Player::Yellow)]
+        );
+
+        // Once column 3 has a disc Yellow's second row can be finished on top of it
+        board.force_place(0, 3, Player::Yellow);
+        assert_eq!(
+            board.winning_cells(),
+            vec![((1, 3), Player::Yellow), ((3, 6), Player::Yellow)]
+        );
+    }
+
+    #[test]
+    fn wins_completed_by_counts_crossing_lines() {
+        let mut board = Board::new(Player::Red);
+
+        // Dropping Red into column 3 finishes the bottom row and the diagonal down from (3, 0)
+        board.force_place(0, 0, Player::Red);
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+        board.force_place(1, 0, Player::Yellow);
+        board.force_place(2, 0, Player::Yellow);
+        board.force_place(3, 0, Player::Red);
+        board.force_place(1, 1, Player::Yellow);
+        board.force_place(2, 1, Player::Red);
+        board.force_place(1, 2, Player::Red);
+        board.print();
+
+        assert_eq!(board.wins_completed_by(3, Player::Red), 2);
+        assert_eq!(board.wins_completed_by(3, Player::Yellow), 0);
+        assert_eq!(board.wins_completed_by(4, Player::Red), 0);
+        assert_eq!(board.wins_completed_by(9, Player::Red), 0);
+    }
+
+    #[test]
+    fn open_threes_find_completion_cells() {
+        let mut board = Board::new(Player::Red);
+
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+        board.force_place(0, 3, Player::Red);
+        board.force_place(0, 4, Player::Yellow);
+
+        assert_eq!(
+            board.open_threes(Player::Red),
+            vec![OpenThree {
+                discs: vec![(0, 1), (0, 2), (0, 3)],
+                completion: (0, 0),
+            }]
+        );
+        assert!(board.open_threes(Player::Yellow).is_empty());
+    }
+
+    #[test]
+    fn safe_moves_avoid_opening_a_fork() {
+        let mut board = Board::new(Player::Red);
+
+        // Filling column 3 lets Yellow land on the second row between its two discs, leaving
+        // both ends of the row open
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Yellow);
+        board.force_place(0, 4, Player::Yellow);
+        board.force_place(0, 5, Player::Red);
+        board.force_place(1, 2, Player::Yellow);
+        board.force_place(1, 4, Player::Yellow);
+        board.print();
+
+        let mut after = board.clone();
+        after.game_move(3).unwrap();
+        assert!(after.creates_fork(3, Player::Yellow));
+        assert!(!board.creates_fork(3, Player::Yellow));
+
+        let safe = board.safe_moves();
+        assert!(!safe.contains(&3));
+        assert!(safe.contains(&0));
+        assert!(safe.contains(&6));
+    }
+
+    #[test]
+    fn clusters_join_orthogonal_neighbours() {
+        let mut board = Board::new(Player::Red);
+        board.force_place(0, 0, Player::Red);
+        board.force_place(0, 2, Player::Red);
+        assert_eq!(
+            board.clusters(Player::Red),
+            vec![vec![(0, 0)], vec![(0, 2)]]
+        );
+
+        board.force_place(0, 1, Player::Red);
+        board.force_place(1, 1, Player::Red);
+        // Touching only diagonally keeps Red's disc on the third row apart
+        board.force_place(1, 2, Player::Yellow);
+        board.force_place(2, 2, Player::Red);
+        assert_eq!(
+            board.clusters(Player::Red),
+            vec![vec![(0, 0), (0, 1), (0, 2), (1, 1)], vec![(2, 2)]]
+        );
+        assert_eq!(board.clusters(Player::Yellow), vec![vec![(1, 2)]]);
+    }
+}
@@ -0,0 +1,178 @@
+use super::*;
+
+/// Something legal but probably unintended about a board `BoardBuilder` built.
+#[derive(Clone, PartialEq, Debug)]
+pub enum BuildWarning {
+    /// The win length doesn't fit along these directions, so lines can only be won in the
+    /// others. On a 3x5 board with four to win, only horizontal lines count.
+    UnreachableDirections(Vec<WinDirection>),
+}
+
+/// Builds a board with custom dimensions or win length. The defaults match `Board::new`, a 6x7
+/// grid with four in a row needed to win and Red moving first.
+pub struct BoardBuilder {
+    starting_player: Player,
+    rows: usize,
+    cols: usize,
+    win_length: usize,
+}
+
+impl BoardBuilder {
+    pub fn new() -> BoardBuilder {
+        BoardBuilder {
+            starting_player: Player::Red,
+            rows: 6,
+            cols: 7,
+            win_length: 4,
+        }
+    }
+
+    pub fn starting_player(mut self, starting_player: Player) -> BoardBuilder {
+        self.starting_player = starting_player;
+        self
+    }
+
+    pub fn rows(mut self, rows: usize) -> BoardBuilder {
+        self.rows = rows;
+        self
+    }
+
+    pub fn cols(mut self, cols: usize) -> BoardBuilder {
+        self.cols = cols;
+        self
+    }
+
+    pub fn win_length(mut self, win_length: usize) -> BoardBuilder {
+        self.win_length = win_length;
+        self
+    }
+
+    /// Creates the board, failing if it has no cells, more than 64 columns or if the win length
+    /// doesn't fit in either dimension, since nobody could ever win such a game. Win lengths
+    /// below 2 are rejected too, as the first disc would always win. Use `build_with_warnings`
+    /// to also hear about boards that can only be won in some directions.
+    pub fn build(self) -> Result<Board, String> {
+        self.build_with_warnings().map(|(board, _)| board)
+    }
+
+    /// Creates the board like `build`, also returning warnings about the configuration. When
+    /// the win length is longer than the board is tall or wide, but not both, lines can only be
+    /// won along the longer side, which is reported as `UnreachableDirections`.
+    pub fn build_with_warnings(self) -> Result<(Board, Vec<BuildWarning>), String> {
+        if self.rows == 0 || self.cols == 0 {
+            return Err(format!("A {}x{} board has no cells", self.rows, self.cols));
+        }
+        if self.win_length < 2 {
+            return Err(format!(
+                "A win length of {} is won by the first disc, it must be at least 2",
+                self.win_length
+            ));
+        }
+        if self.cols > 64 {
+            return Err(format!(
+                "Boards are limited to 64 columns, not {}",
+                self.cols
+            ));
+        }
+        if self.win_length > self.rows.max(self.cols) {
+            return Err(format!(
+                "Connect {} can never be won on a {}x{} board",
+                self.win_length, self.rows, self.cols
+            ));
+        }
+
+        let mut warnings = Vec::new();
+        if self.win_length > self.rows.min(self.cols) {
+            let mut unreachable = Vec::new();
+            if self.win_length > self.cols {
+                unreachable.push(WinDirection::Horizontal);
+            }
+            if self.win_length > self.rows {
+                unreachable.push(WinDirection::Vertical);
+            }
+            unreachable.extend([WinDirection::DiagonalUp, WinDirection::DiagonalDown]);
+            warnings.push(BuildWarning::UnreachableDirections(unreachable));
+        }
+        let board =
+            Board::with_dimensions(self.starting_player, self.rows, self.cols, self.win_length);
+        Ok((board, warnings))
+    }
+}
+
+impl Default for BoardBuilder {
+    fn default() -> BoardBuilder {
+        BoardBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_rejects_unwinnable_boards() {
+        let result = BoardBuilder::new().rows(3).cols(3).win_length(5).build();
+        assert!(result.is_err());
+
+        assert!(BoardBuilder::new().rows(0).build().is_err());
+        assert!(BoardBuilder::new().cols(65).build().is_err());
+        assert!(BoardBuilder::new().win_length(0).build().is_err());
+        assert!(BoardBuilder::new().win_length(1).build().is_err());
+        assert!(BoardBuilder::new().win_length(2).build().is_ok());
+    }
+
+    #[test]
+    fn builder_warns_about_unreachable_directions() {
+        let (board, warnings) = BoardBuilder::new()
+            .rows(3)
+            .cols(5)
+            .win_length(4)
+            .build_with_warnings()
+            .unwrap();
+        assert_eq!(
+            warnings,
+            vec![BuildWarning::UnreachableDirections(vec![
+                WinDirection::Vertical,
+                WinDirection::DiagonalUp,
+                WinDirection::DiagonalDown,
+            ])]
+        );
+        assert_eq!(board.dimensions(), (3, 5));
+
+        let (_, warnings) = BoardBuilder::new().build_with_warnings().unwrap();
+        assert!(warnings.is_empty());
+        assert!(BoardBuilder::new()
+            .rows(3)
+            .cols(3)
+            .win_length(5)
+            .build_with_warnings()
+            .is_err());
+    }
+
+    #[test]
+    fn custom_board_plays_with_its_own_win_length() {
+        let mut board = BoardBuilder::new()
+            .rows(4)
+            .cols(5)
+            .win_length(3)
+            .starting_player(Player::Yellow)
+            .build()
+            .unwrap();
+
+        assert_eq!(board.board.len(), 4);
+        assert_eq!(board.board[0].len(), 5);
+        assert_eq!(board.legal_moves(), vec![0, 1, 2, 3, 4]);
+        assert!(board.game_move(5).is_err());
+
+        board.game_move(0).unwrap();
+        board.game_move(0).unwrap();
+        board.game_move(1).unwrap();
+        board.game_move(1).unwrap();
+        assert!(matches!(
+            board.game_move(2),
+            Ok(GameMoveResult::Won(Player::Yellow))
+        ));
+
+        board.print();
+    }
+}
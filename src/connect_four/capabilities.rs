@@ -0,0 +1,60 @@
+use super::*;
+
+/// What this build of the crate supports, so frontends can adapt at runtime, together with
+/// the dimensions `Board::new` uses.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Capabilities {
+    /// The exact solver behind `solve`, which every build includes.
+    pub solver: bool,
+    /// True when compiled for WebAssembly.
+    pub wasm: bool,
+    /// Serde support for boards, which the crate doesn't offer yet.
+    pub serde: bool,
+    /// Parallel batch evaluation, enabled by the `rayon` feature.
+    pub parallel: bool,
+    /// `to_svg`, enabled by the `svg` feature.
+    pub svg: bool,
+    /// `RngSource`, enabled by the `rand` feature.
+    pub rand: bool,
+    pub rows: usize,
+    pub cols: usize,
+    pub win_length: usize,
+}
+
+/// Returns the capabilities of this build of the crate.
+pub fn capabilities() -> Capabilities {
+    let board = Board::new(Player::Red);
+    Capabilities {
+        solver: true,
+        wasm: cfg!(target_arch = "wasm32"),
+        serde: false,
+        parallel: cfg!(feature = "rayon"),
+        svg: cfg!(feature = "svg"),
+        rand: cfg!(feature = "rand"),
+        rows: board.rows,
+        cols: board.cols,
+        win_length: board.win_length,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capabilities_follow_features() {
+        let capabilities = capabilities();
+
+        assert!(capabilities.solver);
+        assert_eq!(capabilities.parallel, cfg!(feature = "rayon"));
+        assert_eq!(capabilities.svg, cfg!(feature = "svg"));
+        assert_eq!(
+            (
+                capabilities.rows,
+                capabilities.cols,
+                capabilities.win_length
+            ),
+            (6, 7, 4)
+        );
+    }
+}
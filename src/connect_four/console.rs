@@ -0,0 +1,282 @@
+use super::*;
+
+/// How far below the best move's search score a move has to fall for `run_coached` to call it
+/// a blunder. Ordinary moves shift the heuristic by a few points, so this mostly catches moves
+/// that throw away a win or walk into a loss the search can see within `DEFAULT_DEPTH` plies.
+pub const BLUNDER_MARGIN: i32 = 100;
+
+impl Board {
+    /// Plays an interactive game with a coach. Before each move the coach shows the evaluation
+    /// and the AI's suggestion, and after a move that scores `BLUNDER_MARGIN` or more below the
+    /// best one it names the better column and offers to take the move back. The moves are
+    /// searched once per position, before the prompt, so mistyped or unplayable columns don't
+    /// cost another search. Players can
+    /// also type `u` to undo and `q` or `quit` to leave. Returns the status of the game when the
+    /// loop ends.
+    ///
+    /// Moves are judged with `ranked_moves` at `DEFAULT_DEPTH` rather than
+    /// `classify_last_move`, since solving the position can take hours early in a 6x7 game. A
+    /// blunder whose loss only shows up beyond the search depth goes unnoticed.
+    pub fn run_coached(&mut self) -> GameStatus {
+        let messages = Messages::default();
+        'turn: loop {
+            self.print();
+            // One search per position serves both the suggestion and the blunder check
+            let ranked = self.ranked_moves(DEFAULT_DEPTH, self.cols);
+            let suggestion = ranked.first().map(|&(col, _)| col);
+            println!("{}", self.eval_message_with(&messages, suggestion));
+            loop {
+                println!(
+                    "{}",
+                    messages::fill(
+                        &messages.prompt,
+                        &[("player", self.player_name(self.current_player))]
+                    )
+                );
+                let input = match read_input() {
+                    Some(input) => input,
+                    None => return self.status(),
+                };
+                match input.trim() {
+                    "q" | "quit" => return self.status(),
+                    "u" => {
+                        if self.undo().is_none() {
+                            println!("{}", messages.nothing_to_undo);
+                            continue;
+                        }
+                        continue 'turn;
+                    }
+                    _ => (),
+                }
+                let col = match input.trim().parse::<usize>() {
+                    Ok(col) => col,
+                    Err(_) => {
+                        println!("{}", messages.invalid_input);
+                        continue;
+                    }
+                };
+                if col == 0 || !self.can_play(col - 1) {
+                    if let Err(e) = self.game_move_one_based(col) {
+                        println!("{}", messages.move_error(e));
+                    }
+                    continue;
+                }
+
+                match self.game_move_one_based(col) {
+                    Ok(GameMoveResult::Valid) => {
+                        if let Some((better, loss)) = blunder(&ranked, col - 1) {
+                            println!(
+                                "{}",
+                                messages::fill(
+                                    &messages.blunder,
+                                    &[
+                                        ("loss", loss.to_string()),
+                                        ("column", (better + 1).to_string())
+                                    ]
+                                )
+                            );
+                            if read_input().is_some_and(|answer| answer.trim() == "y") {
+                                self.undo();
+                            }
+                        }
+                        continue 'turn;
+                    }
+                    Ok(GameMoveResult::Won(p)) => {
+                        let record = self.moves[self.moves.len() - 1];
+                        let (direction, _) = self.winning_line(record.row, record.col).unwrap();
+                        println!(
+                            "{}",
+                            messages.win_message(p, &self.player_name(p), direction)
+                        );
+                        self.print();
+                        return self.status();
+                    }
+                    Ok(GameMoveResult::Stalemate) => {
+                        println!("{}", messages.stalemate);
+                        return self.status();
+                    }
+                    Err(e) => println!("{}", messages.move_error(e)),
+                }
+            }
+        }
+    }
+
+    /// Runs an analysis console in the terminal, reading one command per line:
+    /// `drop <column>` plays a one-based column, `undo` takes the last move back, `eval` prints
+    /// the heuristic score, `best [depth]` the AI's choice, `solve` the game-theoretic value and
+    /// `show` prints the board. `quit` or the end of the input leaves the console.
+    pub fn run_analysis(&mut self) {
+        self.print();
+        loop {
+            let mut input = String::new();
+            match io::stdin().read_line(&mut input) {
+                Ok(0) => return,
+                Ok(_) => (),
+                Err(e) => {
+                    println!("Error getting input: {}", e);
+                    continue;
+                }
+            }
+            match self.analysis_command(&input) {
+                Some(output) => println!("{}", output),
+                None => return,
+            }
+        }
+    }
+
+    /// Carries out one console command and returns what to print, or None if the command
+    /// leaves the console.
+    fn analysis_command(&mut self, input: &str) -> Option<String> {
+        let mut words = input.split_whitespace();
+        let output = match (words.next(), words.next()) {
+            (Some("quit" | "q"), _) => return None,
+            (Some("drop"), Some(col)) => match col.parse::<usize>() {
+                Ok(col) => match self.game_move_one_based(col) {
+                    Ok(_) => self.analysis_status(),
+                    Err(e) => e.to_string(),
+                },
+                Err(_) => format!("{:?} is not a column number", col),
+            },
+            (Some("undo"), _) => match self.undo() {
+                Some(record) => format!(
+                    "Took back {}'s move in column {}",
+                    self.player_name(record.player),
+                    record.col + 1
+                ),
+                None => "There is no move to undo".to_string(),
+            },
+            (Some("eval"), _) => format!(
+                "{} to move: {} ({:.0}% to win)",
+                self.player_name(self.current_player),
+                self.evaluate(self.current_player),
+                self.win_probability(self.current_player) * 100.0
+            ),
+            (Some("best"), depth) => {
+                let depth = match depth.map(str::parse::<u32>) {
+                    None => DEFAULT_DEPTH,
+                    Some(Ok(depth)) => depth,
+                    Some(Err(_)) => return Some("Usage: best [depth]".to_string()),
+                };
+                match self.best_move(depth) {
+                    Some(col) => format!("Best move: column {}", col + 1),
+                    None => "There are no legal moves".to_string(),
+                }
+            }
+            (Some("solve"), _) => self.describe_solution(),
+            (Some("show"), _) => {
+                self.print();
+                self.analysis_status()
+            }
+            _ => "Commands: drop <column>, undo, eval, best [depth], solve, show, quit".to_string(),
+        };
+        Some(output)
+    }
+
+    /// Describes whose turn it is, or how the game ended.
+    fn analysis_status(&self) -> String {
+        match self.status() {
+            GameStatus::InProgress => {
+                format!("{} to move", self.player_name(self.current_player))
+            }
+            GameStatus::Won(p) => format!("{} has won", self.player_name(p)),
+            GameStatus::Stalemate => "The game is a stalemate".to_string(),
+        }
+    }
+
+    /// Describes the solved value of the position from the current player's point of view.
+    fn describe_solution(&self) -> String {
+        if self.status() != GameStatus::InProgress {
+            return self.analysis_status();
+        }
+        let player = self.player_name(self.current_player);
+        let score = self.solve();
+        let plies = match WIN_BASE - score.abs() {
+            1 => "1 ply".to_string(),
+            n => format!("{} plies", n),
+        };
+        match score.signum() {
+            1 => format!("{} wins in {}", player, plies),
+            -1 => format!("{} loses in {}", player, plies),
+            _ => "Best play ends in a stalemate".to_string(),
+        }
+    }
+}
+
+/// Reads a line from stdin, or returns None at the end of the input or on an error.
+fn read_input() -> Option<String> {
+    let mut input = String::new();
+    match io::stdin().read_line(&mut input) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(input),
+    }
+}
+
+/// Compares the played column with the best of the `ranked_moves` scored before it was
+/// played. Returns the best column and how many points the played one gave away, if that is
+/// at least `BLUNDER_MARGIN`.
+fn blunder(ranked: &[(usize, i32)], played: usize) -> Option<(usize, i32)> {
+    let &(best, best_score) = ranked.first()?;
+    let &(_, score) = ranked.iter().find(|&&(col, _)| col == played)?;
+    let loss = best_score.saturating_sub(score);
+    if loss >= BLUNDER_MARGIN {
+        Some((best, loss))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn analysis_commands_drive_the_board() {
+        let mut board = Board::new(Player::Red);
+
+        assert_eq!(
+            board.analysis_command("drop 4\n"),
+            Some("Yellow to move".to_string())
+        );
+        assert_eq!(board.column_height(3), 1);
+        assert_eq!(
+            board.analysis_command("drop 9"),
+            Some("Column 9 is invalid".to_string())
+        );
+        assert_eq!(
+            board.analysis_command("undo"),
+            Some("Took back Red's move in column 4".to_string())
+        );
+        assert_eq!(
+            board.analysis_command("best 2"),
+            Some("Best move: column 4".to_string())
+        );
+        assert_eq!(board.analysis_command("quit"), None);
+    }
+
+    #[test]
+    fn solve_command_reports_forced_win() {
+        let mut board = Board::new(Player::Red);
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+        board.force_place(0, 3, Player::Red);
+
+        assert_eq!(
+            board.analysis_command("solve"),
+            Some("Red wins in 1 ply".to_string())
+        );
+    }
+
+    #[test]
+    fn blunder_flags_ignoring_a_threat() {
+        let mut board = Board::new(Player::Red);
+        board.force_place(0, 0, Player::Yellow);
+        board.force_place(0, 1, Player::Yellow);
+        board.force_place(0, 2, Player::Yellow);
+
+        let ranked = board.ranked_moves(2, 7);
+        assert_eq!(blunder(&ranked, 3), None);
+        let (better, loss) = blunder(&ranked, 6).unwrap();
+        assert_eq!(better, 3);
+        assert!(loss >= BLUNDER_MARGIN);
+    }
+}
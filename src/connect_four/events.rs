@@ -0,0 +1,126 @@
+use super::*;
+
+/// Something that happened during a move, for frontends that drive sounds or animations.
+#[derive(Clone, PartialEq, Debug)]
+pub enum MoveEvent {
+    Dropped { col: usize, row: usize },
+    TurnChanged(Player),
+    WinningLine(Vec<(usize, usize)>),
+    Draw,
+}
+
+impl Board {
+    /// Plays a move like `game_move` and describes what happened as a list of events, in the
+    /// order they occurred. A disc landing is always reported first, followed by either the
+    /// turn passing to the other player, the winning line or a draw. With `stop_on_win` off a
+    /// winning line is reported and the turn still passes on. With `report_all_lines` set, a
+    /// move completing several lines reports each of them.
+    pub fn game_move_events(&mut self, col: usize) -> Result<Vec<MoveEvent>, MoveError> {
+        let move_count = self.moves.len();
+        let win_count = self.wins().len();
+        let result = self.game_move(col)?;
+
+        let mut events = Vec::new();
+        if self.moves.len() > move_count {
+            let record = self.moves[move_count];
+            events.push(MoveEvent::Dropped {
+                col: record.col,
+                row: record.row,
+            });
+            if let GameMoveResult::Won(_) = result {
+                let mut lines = self.winning_lines(record.row, record.col);
+                if !self.report_all_lines {
+                    lines.truncate(1);
+                }
+                for (_, line) in lines {
+                    events.push(MoveEvent::WinningLine(line));
+                }
+            } else {
+                for (_, line) in &self.wins()[win_count..] {
+                    events.push(MoveEvent::WinningLine(line.clone()));
+                }
+            }
+        }
+        match result {
+            GameMoveResult::Valid => events.push(MoveEvent::TurnChanged(self.current_player)),
+            GameMoveResult::Won(_) => (),
+            GameMoveResult::Stalemate => events.push(MoveEvent::Draw),
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_for_normal_and_winning_moves() {
+        let mut board = Board::new(Player::Red);
+
+        assert_eq!(
+            board.game_move_events(3).unwrap(),
+            vec![
+                MoveEvent::Dropped { col: 3, row: 0 },
+                MoveEvent::TurnChanged(Player::Yellow)
+            ]
+        );
+
+        board.force_place(1, 0, Player::Red);
+        board.force_place(1, 1, Player::Red);
+        board.force_place(1, 2, Player::Red);
+        board.game_move(6).unwrap();
+
+        assert_eq!(
+            board.game_move_events(3).unwrap(),
+            vec![
+                MoveEvent::Dropped { col: 3, row: 1 },
+                MoveEvent::WinningLine(vec![(1, 0), (1, 1), (1, 2), (1, 3)])
+            ]
+        );
+    }
+
+    #[test]
+    fn events_report_win_and_continue_without_stop_on_win() {
+        let mut board = Board::new(Player::Red);
+        board.stop_on_win = false;
+        for _ in 0..3 {
+            board.game_move(0).unwrap();
+            board.game_move(1).unwrap();
+        }
+
+        assert_eq!(
+            board.game_move_events(0).unwrap(),
+            vec![
+                MoveEvent::Dropped { col: 0, row: 3 },
+                MoveEvent::WinningLine(vec![(0, 0), (1, 0), (2, 0), (3, 0)]),
+                MoveEvent::TurnChanged(Player::Yellow)
+            ]
+        );
+    }
+
+    #[test]
+    fn events_report_every_line_when_asked() {
+        let mut board = Board::new(Player::Red);
+        board.force_place(0, 0, Player::Red);
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+        // The drop into column 3 also finishes the diagonal falling from (3, 0)
+        board.force_place(1, 2, Player::Red);
+        board.force_place(2, 1, Player::Red);
+        board.force_place(3, 0, Player::Red);
+
+        let mut first_only = board.clone();
+        assert_eq!(first_only.game_move_events(3).unwrap().len(), 2);
+
+        board.report_all_lines = true;
+        assert_eq!(
+            board.game_move_events(3).unwrap(),
+            vec![
+                MoveEvent::Dropped { col: 3, row: 0 },
+                MoveEvent::WinningLine(vec![(0, 0), (0, 1), (0, 2), (0, 3)]),
+                MoveEvent::WinningLine(vec![(3, 0), (2, 1), (1, 2), (0, 3)]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use super::*;
+
+/// Key mixed into the hash when Yellow is the player to move.
+const YELLOW_TO_MOVE: u64 = 0x9e37_79b9_7f4a_7c15;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl Board {
+    /// Returns the Zobrist hash of the position: the XOR of a fixed pseudo-random key for every
+    /// occupied cell and its owner, plus a key for the player to move. Equal positions on boards
+    /// of the same size always hash the same, across runs as well.
+    pub fn zobrist_hash(&self) -> u64 {
+        self.hash_with(|col| col)
+    }
+
+    /// Returns the same value as `zobrist_hash` without scanning the board. The disc keys are
+    /// kept up to date as cells change and the key for the player to move is mixed in here, so
+    /// the hash is right even after `current_player` is set directly.
+    pub fn current_hash(&self) -> u64 {
+        self.hash ^ side_to_move_key(self.current_player)
+    }
+
+    /// Returns an identifier for the game made from the starting player and every move played,
+    /// hashed with FNV-1a so the same game gets the same ID on every run and platform. Games
+    /// that reach the same position in a different order get different IDs.
+    pub fn game_id(&self) -> u64 {
+        let first = self
+            .moves
+            .first()
+            .map_or(self.current_player, |record| record.player);
+        let mut bytes = vec![match first {
+            Player::Red => 0,
+            Player::Yellow => 1,
+        }];
+        bytes.extend(self.moves.iter().map(|record| record.col as u8));
+        bytes.iter().fold(FNV_OFFSET, |hash, &byte| {
+            (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+        })
+    }
+
+    /// Returns a key that is the same for a position and its left-right mirror image, the
+    /// smaller of the two positions' Zobrist hashes, so an opening book only needs to store one
+    /// of them.
+    pub fn canonical_key(&self) -> u64 {
+        self.zobrist_hash()
+            .min(self.hash_with(|col| self.cols - 1 - col))
+    }
+
+    /// Hashes the position with each column moved to `map_col(col)`.
+    fn hash_with(&self, map_col: impl Fn(usize) -> usize) -> u64 {
+        let mut hash = side_to_move_key(self.current_player);
+        for (row, cells) in self.board.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                if let Cell::Player(player) = cell {
+                    hash ^= self.zobrist_key(row, map_col(col), *player);
+                }
+            }
+        }
+        hash
+    }
+
+    /// Returns the key for a disc of the given player at the given cell, derived from the cell
+    /// index with the splitmix64 mixer so no table has to be stored.
+    pub(crate) fn zobrist_key(&self, row: usize, col: usize, player: Player) -> u64 {
+        let index = (row * self.cols + col) * 2
+            + match player {
+                Player::Red => 0,
+                Player::Yellow => 1,
+            };
+        let mut z = (index as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+fn side_to_move_key(player: Player) -> u64 {
+    match player {
+        Player::Red => 0,
+        Player::Yellow => YELLOW_TO_MOVE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_key_matches_mirror() {
+        let mut board = Board::new(Player::Red);
+        let mut mirror = Board::new(Player::Red);
+        for col in [0, 3, 1] {
+            board.game_move(col).unwrap();
+            mirror.game_move(6 - col).unwrap();
+        }
+
+        assert_ne!(board.zobrist_hash(), mirror.zobrist_hash());
+        assert_eq!(board.canonical_key(), mirror.canonical_key());
+
+        let mut other = Board::new(Player::Red);
+        for col in [0, 3, 2] {
+            other.game_move(col).unwrap();
+        }
+        assert_ne!(board.canonical_key(), other.canonical_key());
+    }
+
+    #[test]
+    fn zobrist_hash_depends_on_player_to_move() {
+        let mut board = Board::new(Player::Red);
+        let empty = board.zobrist_hash();
+        board.current_player = Player::Yellow;
+        assert_ne!(board.zobrist_hash(), empty);
+    }
+
+    #[test]
+    fn current_hash_follows_moves_and_undo() {
+        let mut board = Board::new(Player::Red);
+        assert_eq!(board.current_hash(), board.zobrist_hash());
+
+        for col in [3, 3, 2, 4, 4, 0] {
+            board.game_move(col).unwrap();
+            assert_eq!(board.current_hash(), board.zobrist_hash());
+        }
+        board.force_place(5, 6, Player::Yellow);
+        assert_eq!(board.current_hash(), board.zobrist_hash());
+
+        while board.undo().is_some() {
+            assert_eq!(board.current_hash(), board.zobrist_hash());
+        }
+        board.compact_gravity();
+        assert_eq!(board.current_hash(), board.zobrist_hash());
+    }
+
+    #[test]
+    fn game_id_is_stable_and_order_sensitive() {
+        let mut board = Board::new(Player::Red);
+        let mut transposed = Board::new(Player::Red);
+        for col in [3, 2, 4] {
+            board.game_move(col).unwrap();
+        }
+        for col in [4, 2, 3] {
+            transposed.game_move(col).unwrap();
+        }
+
+        assert_eq!(board.game_id(), board.clone().game_id());
+        assert_eq!(board.game_id(), 0x332f_d57f_8f1b_e512);
+        assert_ne!(board.game_id(), transposed.game_id());
+        assert_ne!(
+            Board::new(Player::Red).game_id(),
+            Board::new(Player::Yellow).game_id()
+        );
+    }
+}
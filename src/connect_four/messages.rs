@@ -0,0 +1,174 @@
+use super::*;
+
+/// The text printed by `run_game_with`, so the interactive game can be reworded or translated.
+/// Messages can contain placeholders in braces, listed on each field, which are filled in when
+/// the message is printed.
+#[derive(Clone, Debug)]
+pub struct Messages {
+    /// Asks for the next move. Placeholders: `{player}`.
+    pub prompt: String,
+    /// Announces a connect four. Placeholders: `{player}`, `{direction}` for the direction of
+    /// the winning line, such as `a vertical line`.
+    pub win: String,
+    /// Announcements for Red's and Yellow's wins, in that order, used instead of `win` when
+    /// set. Placeholders: the same as `win`.
+    pub win_by_player: Option<(String, String)>,
+    pub stalemate: String,
+    /// Placeholders: `{loser}`, `{winner}`.
+    pub resign: String,
+    /// Shown when the input isn't a number or a command.
+    pub invalid_input: String,
+    pub nothing_to_undo: String,
+    /// Shown when reading from stdin fails. Placeholders: `{error}`.
+    pub input_error: String,
+    /// Placeholders: `{column}`.
+    pub invalid_column: String,
+    /// Placeholders: `{column}`.
+    pub column_full: String,
+    pub game_over: String,
+    pub pass_not_allowed: String,
+    /// Placeholders: `{moves}`.
+    pub move_limit_reached: String,
+    /// Coaching shown after each move when `show_eval` is set. Placeholders: `{player}` to
+    /// move, `{score}` from `evaluate`, `{percent}` from `win_probability` and `{column}` for
+    /// the AI's suggestion.
+    pub eval: String,
+    /// Warns about a blunder in `run_coached` and offers to take it back, answered with `y` or
+    /// `n`. Placeholders: `{loss}` for the points given away, `{column}` for the better move.
+    pub blunder: String,
+    /// Offers the second player a swap under the pie rule, answered with `y` or `n`.
+    /// Placeholders: `{player}`, `{opener}` for the player who made the opening move.
+    pub swap_offer: String,
+}
+
+impl Default for Messages {
+    fn default() -> Messages {
+        Messages {
+            prompt: "Player {player}, enter a move: ".to_string(),
+            win: "{player} has a connect 4!\n".to_string(),
+            win_by_player: None,
+            stalemate: "Gameover, Stalemate".to_string(),
+            resign: "{loser} resigns, {winner} wins!".to_string(),
+            invalid_input: "Please enter a valid column number".to_string(),
+            nothing_to_undo: "There is no move to undo".to_string(),
+            input_error: "Error getting input: {error}".to_string(),
+            invalid_column: "Column {column} is invalid".to_string(),
+            column_full: "Column {column} is full.".to_string(),
+            game_over: "The game is already over.".to_string(),
+            pass_not_allowed: "Passing is not allowed.".to_string(),
+            move_limit_reached: "The game didn't end within {moves} moves.".to_string(),
+            eval: "{player} to move: {score} ({percent}% to win), suggested move: column {column}"
+                .to_string(),
+            blunder: "That move gives away {loss} points, column {column} was better. Take it back? (y/n)"
+                .to_string(),
+            swap_offer: "{player}, swap sides and take over {opener}'s opening move? (y/n)"
+                .to_string(),
+        }
+    }
+}
+
+impl Messages {
+    /// Returns the announcement for the player's win, naming them and the direction of their
+    /// winning line.
+    pub(crate) fn win_message(
+        &self,
+        player: Player,
+        name: &str,
+        direction: WinDirection,
+    ) -> String {
+        let template = match (&self.win_by_player, player) {
+            (Some((red, _)), Player::Red) => red,
+            (Some((_, yellow)), Player::Yellow) => yellow,
+            (None, _) => &self.win,
+        };
+        let direction = match direction {
+            WinDirection::Horizontal => "a horizontal line",
+            WinDirection::Vertical => "a vertical line",
+            WinDirection::DiagonalUp => "a rising diagonal",
+            WinDirection::DiagonalDown => "a falling diagonal",
+        };
+        fill(template, &[("player", name), ("direction", direction)])
+    }
+
+    /// Returns the message describing a rejected move.
+    pub(crate) fn move_error(&self, error: MoveError) -> String {
+        match error {
+            MoveError::InvalidColumn(col) => fill(&self.invalid_column, &[("column", col)]),
+            MoveError::ColumnFull(col) => fill(&self.column_full, &[("column", col)]),
+            MoveError::GameOver => self.game_over.clone(),
+            MoveError::PassNotAllowed => self.pass_not_allowed.clone(),
+            MoveError::MoveLimitReached(moves) => {
+                fill(&self.move_limit_reached, &[("moves", moves)])
+            }
+        }
+    }
+}
+
+/// Replaces each `{name}` placeholder in the template with its value.
+pub(crate) fn fill(template: &str, values: &[(&str, impl ToString)]) -> String {
+    let mut message = template.to_string();
+    for (name, value) in values {
+        message = message.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_messages_match_move_errors() {
+        let messages = Messages::default();
+
+        for error in [
+            MoveError::InvalidColumn(9),
+            MoveError::ColumnFull(3),
+            MoveError::GameOver,
+            MoveError::PassNotAllowed,
+            MoveError::MoveLimitReached(42),
+        ] {
+            assert_eq!(messages.move_error(error), error.to_string());
+        }
+    }
+
+    #[test]
+    fn custom_messages_fill_placeholders() {
+        let messages = Messages {
+            prompt: "Joueur {player}, à vous : ".to_string(),
+            column_full: "La colonne {column} est pleine.".to_string(),
+            ..Messages::default()
+        };
+
+        assert_eq!(
+            fill(&messages.prompt, &[("player", "Rouge")]),
+            "Joueur Rouge, à vous : "
+        );
+        assert_eq!(
+            messages.move_error(MoveError::ColumnFull(4)),
+            "La colonne 4 est pleine."
+        );
+    }
+
+    #[test]
+    fn win_messages_per_player() {
+        let mut messages = Messages::default();
+        assert_eq!(
+            messages.win_message(Player::Yellow, "Bob", WinDirection::Vertical),
+            "Bob has a connect 4!\n"
+        );
+
+        messages.win_by_player = Some((
+            "🎉 {player} wins with {direction}!".to_string(),
+            "{player} sneaks a win with {direction}".to_string(),
+        ));
+        assert_eq!(
+            messages.win_message(Player::Red, "Alice", WinDirection::DiagonalUp),
+            "🎉 Alice wins with a rising diagonal!"
+        );
+        assert_eq!(
+            messages.win_message(Player::Yellow, "Bob", WinDirection::Horizontal),
+            "Bob sneaks a win with a horizontal line"
+        );
+    }
+}
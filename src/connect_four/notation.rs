@@ -0,0 +1,336 @@
+use super::*;
+
+impl Board {
+    /// Writes the game as numbered move pairs using one-based columns, followed by a result
+    /// marker: `1-0` if the player who moved first won, `0-1` if the other player won,
+    /// `1/2-1/2` for a stalemate and `*` for a game still in progress.
+    /// For example `1. R4 Y3 2. R4 Y3 3. R4 Y3 4. R4 1-0`.
+    pub fn to_transcript(&self) -> String {
+        let first = self
+            .moves
+            .first()
+            .map_or(self.current_player, |record| record.player);
+
+        let mut parts = Vec::new();
+        for (i, (player, col)) in self.history().enumerate() {
+            if i % 2 == 0 {
+                parts.push(format!("{}.", i / 2 + 1));
+            }
+            let letter = match player {
+                Player::Red => 'R',
+                Player::Yellow => 'Y',
+            };
+            parts.push(format!("{}{}", letter, col + 1));
+        }
+
+        parts.push(
+            match self.status() {
+                GameStatus::InProgress => "*",
+                GameStatus::Won(p) if p == first => "1-0",
+                GameStatus::Won(_) => "0-1",
+                GameStatus::Stalemate => "1/2-1/2",
+            }
+            .to_string(),
+        );
+        parts.join(" ")
+    }
+
+    /// Names a cell in algebraic notation: a column letter from `a` for the leftmost column
+    /// followed by the row number counted from 1 at the bottom, so `d1` is the bottom of the
+    /// centre column of a standard board. Columns past `z` carry on with two letters, `aa`,
+    /// `ab` and so on, like spreadsheet columns.
+    pub fn cell_name(row: usize, col: usize) -> String {
+        let mut letters = Vec::new();
+        let mut n = col + 1;
+        while n > 0 {
+            n -= 1;
+            letters.push((b'a' + (n % 26) as u8) as char);
+            n /= 26;
+        }
+        letters.reverse();
+        format!("{}{}", letters.into_iter().collect::<String>(), row + 1)
+    }
+
+    /// Parses a cell name written by `cell_name` back into `(row, col)`, accepting upper case
+    /// letters too. Returns None if the name isn't one or more letters followed by a row number
+    /// from 1.
+    pub fn parse_cell_name(name: &str) -> Option<(usize, usize)> {
+        let name = name.trim();
+        let split = name
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(name.len());
+        let (letters, row) = name.split_at(split);
+        if letters.is_empty() {
+            return None;
+        }
+        let col = letters.bytes().try_fold(0usize, |col, letter| {
+            col.checked_mul(26)?
+                .checked_add((letter.to_ascii_lowercase() - b'a') as usize + 1)
+        })?;
+        let row: usize = row.parse().ok()?;
+        Some((row.checked_sub(1)?, col - 1))
+    }
+
+    /// Returns the discs in each column from the bottom up, leftmost column first. Gaps under
+    /// floating discs are skipped.
+    pub fn to_stacks(&self) -> Vec<Vec<Player>> {
+        (0..self.cols)
+            .map(|col| {
+                (0..self.rows)
+                    .filter_map(|row| match self.board[row][col] {
+                        Cell::Player(p) => Some(p),
+                        Cell::Empty => None,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Builds a standard board from per-column stacks as written by `to_stacks`. The discs were
+    /// played alternately starting with `starting`, so the player to move follows from the
+    /// disc counts. Fails if the number of columns is wrong, a stack is taller than the board
+    /// or the counts couldn't have come from alternating moves. Stacks holding a connect four
+    /// load as a game won by its owner.
+    pub fn from_stacks(starting: Player, stacks: &[Vec<Player>]) -> Result<Board, String> {
+        let mut board = Board::new(starting);
+        if stacks.len() != board.cols {
+            return Err(format!(
+                "Expected {} columns but found {}",
+                board.cols,
+                stacks.len()
+            ));
+        }
+
+        let mut first = 0;
+        let mut second = 0;
+        for (col, stack) in stacks.iter().enumerate() {
+            if stack.len() > board.rows {
+                return Err(format!(
+                    "Column {} holds {} discs but only {} fit",
+                    col,
+                    stack.len(),
+                    board.rows
+                ));
+            }
+            for (row, &player) in stack.iter().enumerate() {
+                if player == starting {
+                    first += 1;
+                } else {
+                    second += 1;
+                }
+                board.update_cell(row, col, Cell::Player(player));
+            }
+        }
+
+        board.current_player = match first as isize - second as isize {
+            0 => starting,
+            1 => starting.opponent(),
+            _ => {
+                return Err(format!(
+                    "{} discs for {:?} and {} for {:?} can't be reached",
+                    first,
+                    starting,
+                    second,
+                    starting.opponent()
+                ))
+            }
+        };
+        board.move_count = first + second;
+        board.settle_status();
+        Ok(board)
+    }
+
+    /// Loads a position from a row of the UCI Connect-4 dataset: 42 comma-separated `x`, `o`
+    /// or `b` values listing each column from bottom to top, leftmost column first, with `x`
+    /// for Red, `o` for Yellow and `b` for an empty cell. A trailing `win`, `loss` or `draw`
+    /// label is ignored. Red is taken to have moved first, so the player to move follows from
+    /// the disc counts, and a row holding a connect four loads as a game won by its owner.
+    pub fn from_dataset_row(row: &str) -> Result<Board, String> {
+        let mut values: Vec<&str> = row.trim().split(',').map(str::trim).collect();
+        if matches!(values.last(), Some(&"win" | &"loss" | &"draw")) {
+            values.pop();
+        }
+
+        let mut board = Board::new(Player::Red);
+        if values.len() != board.rows * board.cols {
+            return Err(format!(
+                "Expected {} values but found {}",
+                board.rows * board.cols,
+                values.len()
+            ));
+        }
+
+        let mut counts: (usize, usize) = (0, 0);
+        for (i, value) in values.iter().enumerate() {
+            let (col, row) = (i / board.rows, i % board.rows);
+            let cell = match *value {
+                "x" => Cell::Player(Player::Red),
+                "o" => Cell::Player(Player::Yellow),
+                "b" => continue,
+                other => return Err(format!("Unknown cell value {:?}", other)),
+            };
+            match cell {
+                Cell::Player(Player::Red) => counts.0 += 1,
+                _ => counts.1 += 1,
+            }
+            board.update_cell(row, col, cell);
+        }
+
+        if let Some((row, col)) = board.has_floating_discs() {
+            return Err(format!("Disc at row {} of column {} is floating", row, col));
+        }
+
+        board.current_player = match counts {
+            (red, yellow) if red == yellow => Player::Red,
+            (red, yellow) if red == yellow + 1 => Player::Yellow,
+            _ => {
+                return Err(format!(
+                    "{} Red and {} Yellow discs can't be reached",
+                    counts.0, counts.1
+                ))
+            }
+        };
+        board.move_count = counts.0 + counts.1;
+        board.settle_status();
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transcript_of_won_game() {
+        let mut board = Board::new(Player::Red);
+        for _ in 0..3 {
+            board.game_move(3).unwrap();
+            board.game_move(2).unwrap();
+        }
+        board.game_move(3).unwrap();
+
+        assert_eq!(
+            board.to_transcript(),
+            "1. R4 Y3 2. R4 Y3 3. R4 Y3 4. R4 1-0"
+        );
+    }
+
+    #[test]
+    fn transcript_of_unfinished_game() {
+        let mut board = Board::new(Player::Yellow);
+        assert_eq!(board.to_transcript(), "*");
+
+        board.game_move(0).unwrap();
+        board.game_move(6).unwrap();
+        assert_eq!(board.to_transcript(), "1. Y1 R7 *");
+
+        board.resign();
+        assert_eq!(board.to_transcript(), "1. Y1 R7 0-1");
+    }
+
+    #[test]
+    fn from_dataset_row_reads_columns_bottom_up() {
+        let mut values = vec!["b"; 42];
+        values[0] = "x";
+        values[1] = "o";
+        values[18] = "x";
+        let mut row = values.join(",");
+        row.push_str(",win");
+
+        let board = Board::from_dataset_row(&row).unwrap();
+        board.print();
+
+        assert_eq!(board.board[0][0], Cell::Player(Player::Red));
+        assert_eq!(board.board[1][0], Cell::Player(Player::Yellow));
+        assert_eq!(board.board[0][3], Cell::Player(Player::Red));
+        assert_eq!(board.current_player, Player::Yellow);
+        assert_eq!(board.move_count, 3);
+    }
+
+    #[test]
+    fn from_dataset_row_rejects_bad_rows() {
+        assert!(Board::from_dataset_row("x,o,b").is_err());
+
+        let mut values = vec!["b"; 42];
+        values[1] = "x";
+        assert!(Board::from_dataset_row(&values.join(",")).is_err());
+
+        values[0] = "o";
+        values[1] = "o";
+        assert!(Board::from_dataset_row(&values.join(",")).is_err());
+
+        values[1] = "q";
+        assert!(Board::from_dataset_row(&values.join(",")).is_err());
+    }
+
+    #[test]
+    fn from_dataset_row_settles_a_connect_four() {
+        // Red's first four discs in column 0, Yellow's three in column 1
+        let mut values = vec!["b"; 42];
+        values[..4].fill("x");
+        values[6..9].fill("o");
+
+        let mut board = Board::from_dataset_row(&values.join(",")).unwrap();
+        assert_eq!(board.status(), GameStatus::Won(Player::Red));
+        assert_eq!(board.game_move(2).err(), Some(MoveError::GameOver));
+    }
+
+    #[test]
+    fn cell_names_round_trip() {
+        assert_eq!(Board::cell_name(0, 3), "d1");
+        assert_eq!(Board::cell_name(5, 0), "a6");
+        assert_eq!(Board::parse_cell_name("a6"), Some((5, 0)));
+        assert_eq!(Board::parse_cell_name("G2"), Some((1, 6)));
+
+        for name in ["", "a", "a0", "4d", "d-1"] {
+            assert_eq!(Board::parse_cell_name(name), None);
+        }
+    }
+
+    #[test]
+    fn cell_names_on_wide_boards() {
+        assert_eq!(Board::cell_name(0, 25), "z1");
+        assert_eq!(Board::cell_name(0, 26), "aa1");
+        assert_eq!(Board::cell_name(2, 200), "gs3");
+        assert_eq!(Board::parse_cell_name("AA1"), Some((0, 26)));
+
+        for col in 0..1000 {
+            let name = Board::cell_name(4, col);
+            assert_eq!(Board::parse_cell_name(&name), Some((4, col)), "{}", name);
+        }
+    }
+
+    #[test]
+    fn stacks_round_trip() {
+        let mut board = Board::new(Player::Yellow);
+        for col in [3, 3, 2, 6, 3] {
+            board.game_move(col).unwrap();
+        }
+
+        let stacks = board.to_stacks();
+        assert_eq!(stacks[3], vec![Player::Yellow, Player::Red, Player::Yellow]);
+        assert!(stacks[0].is_empty());
+
+        let loaded = Board::from_stacks(Player::Yellow, &stacks).unwrap();
+        assert_eq!(loaded.board, board.board);
+        assert_eq!(loaded.current_player, board.current_player);
+        assert_eq!(loaded.move_count, 5);
+
+        assert!(Board::from_stacks(Player::Red, &stacks).is_err());
+        assert!(Board::from_stacks(Player::Red, &stacks[..6]).is_err());
+        let mut tall = vec![Vec::new(); 7];
+        tall[0] = [Player::Red, Player::Yellow].repeat(4);
+        assert!(Board::from_stacks(Player::Red, &tall).is_err());
+    }
+
+    #[test]
+    fn from_stacks_settles_a_connect_four() {
+        let mut stacks = vec![Vec::new(); 7];
+        stacks[0] = vec![Player::Red; 4];
+        stacks[1] = vec![Player::Yellow; 3];
+
+        let mut board = Board::from_stacks(Player::Red, &stacks).unwrap();
+        assert_eq!(board.status(), GameStatus::Won(Player::Red));
+        assert_eq!(board.game_move(2).err(), Some(MoveError::GameOver));
+    }
+}
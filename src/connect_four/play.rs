@@ -0,0 +1,168 @@
+use super::*;
+
+/// How many times in a row `play_to_completion` asks a source for another move after its
+/// `rejected` asked to try again, before ending play with the error.
+pub const MAX_RETRIES: usize = 10;
+
+/// Chooses moves for one side of a headless game played by `play_to_completion`, so servers
+/// and tournaments can plug in AIs, scripts or remote players. Closures taking the board and
+/// returning a column work as sources too.
+pub trait MoveSource {
+    /// Returns the column to play for the current player of the board, or None to stop the
+    /// game where it is.
+    fn next_move(&mut self, board: &Board) -> Option<usize>;
+
+    /// Called when the source returned a column that can't be played. Returning true asks the
+    /// source for another move, which suits interactive sources that re-prompt the player. By
+    /// default play ends with the error.
+    fn rejected(&mut self, _col: usize, _error: MoveError) -> bool {
+        false
+    }
+}
+
+impl<F: FnMut(&Board) -> Option<usize>> MoveSource for F {
+    fn next_move(&mut self, board: &Board) -> Option<usize> {
+        self(board)
+    }
+}
+
+/// A source that plays `best_move` at the given depth.
+pub struct AiSource(pub u32);
+
+impl MoveSource for AiSource {
+    fn next_move(&mut self, board: &Board) -> Option<usize> {
+        board.best_move(self.0)
+    }
+}
+
+impl Board {
+    /// Plays the game out, asking `red` and `yellow` for the moves of their colour in turn,
+    /// and returns how it ended, or the status when a source returned None. Each column is
+    /// checked with `can_play` before it is played, and one that can't be played ends play
+    /// with `MoveError::InvalidColumn` or `MoveError::ColumnFull` unless the source's
+    /// `rejected` asks to try again, which it can do up to `MAX_RETRIES` times a turn. No more
+    /// than `rows * cols` moves are played, so a misbehaving source can never keep the game
+    /// going.
+    pub fn play_to_completion(
+        &mut self,
+        red: &mut dyn MoveSource,
+        yellow: &mut dyn MoveSource,
+    ) -> Result<GameStatus, MoveError> {
+        let limit = self.rows * self.cols;
+        for _ in 0..limit {
+            if self.status() != GameStatus::InProgress {
+                return Ok(self.status());
+            }
+            let source: &mut dyn MoveSource = match self.current_player {
+                Player::Red => red,
+                Player::Yellow => yellow,
+            };
+            let mut retries = 0;
+            let col = loop {
+                let col = match source.next_move(self) {
+                    Some(col) => col,
+                    None => return Ok(self.status()),
+                };
+                if self.can_play(col) {
+                    break col;
+                }
+                let error = if col >= self.cols {
+                    MoveError::InvalidColumn(col)
+                } else {
+                    MoveError::ColumnFull(col)
+                };
+                if retries == MAX_RETRIES || !source.rejected(col, error) {
+                    return Err(error);
+                }
+                retries += 1;
+            };
+            self.game_move(col)?;
+        }
+        match self.status() {
+            GameStatus::InProgress => Err(MoveError::MoveLimitReached(limit)),
+            status => Ok(status),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ai_sources_finish_the_game() {
+        let mut board = Board::new(Player::Red);
+
+        let status = board
+            .play_to_completion(&mut AiSource(2), &mut AiSource(1))
+            .unwrap();
+        assert_ne!(status, GameStatus::InProgress);
+        assert_eq!(status, board.status());
+    }
+
+    #[test]
+    fn illegal_source_errors_out() {
+        let mut board = Board::new(Player::Red);
+        let mut red_calls = 0;
+        let mut red = |_: &Board| {
+            red_calls += 1;
+            Some(0)
+        };
+        let mut yellow = |_: &Board| Some(0);
+
+        // The colours alternate up column 0 until Red tries to play into it once it is full
+        assert_eq!(
+            board.play_to_completion(&mut red, &mut yellow),
+            Err(MoveError::ColumnFull(0))
+        );
+        assert_eq!(red_calls, 4);
+        assert_eq!(board.column_height(0), 6);
+
+        let mut board = Board::new(Player::Red);
+        assert_eq!(
+            board.play_to_completion(&mut |_: &Board| Some(99), &mut yellow),
+            Err(MoveError::InvalidColumn(99))
+        );
+    }
+
+    /// Replays scripted columns, asking to be tried again after an illegal one.
+    struct Forgiving(Vec<usize>, Vec<MoveError>);
+
+    impl MoveSource for Forgiving {
+        fn next_move(&mut self, _board: &Board) -> Option<usize> {
+            Some(self.0.remove(0))
+        }
+
+        fn rejected(&mut self, _col: usize, error: MoveError) -> bool {
+            self.1.push(error);
+            true
+        }
+    }
+
+    #[test]
+    fn rejected_source_is_asked_again() {
+        let mut board = Board::new(Player::Red);
+        let mut red = Forgiving(vec![99, 0, 0, 0, 0], Vec::new());
+        let mut yellow = |_: &Board| Some(1);
+
+        assert_eq!(
+            board.play_to_completion(&mut red, &mut yellow),
+            Ok(GameStatus::Won(Player::Red))
+        );
+        assert_eq!(red.1, vec![MoveError::InvalidColumn(99)]);
+    }
+
+    #[test]
+    fn retries_run_out() {
+        let mut board = Board::new(Player::Red);
+        let mut red = Forgiving(vec![99; MAX_RETRIES + 1], Vec::new());
+        let mut yellow = |_: &Board| Some(1);
+
+        assert_eq!(
+            board.play_to_completion(&mut red, &mut yellow),
+            Err(MoveError::InvalidColumn(99))
+        );
+        assert!(red.0.is_empty());
+        assert_eq!(red.1.len(), MAX_RETRIES);
+    }
+}
@@ -0,0 +1,50 @@
+use super::*;
+
+/// Builds a random position where the current player has exactly one column that wins
+/// immediately. Random games are played out from an empty board until one lands on such a
+/// position, so the result is always reachable through legal play.
+pub fn generate_mate_in_one(rng: &mut impl RandomSource) -> Board {
+    loop {
+        let mut board = Board::new(Player::Red);
+        let plies = 6 + rng.next_index(24);
+        let mut finished = false;
+
+        for _ in 0..plies {
+            let col = board.random_move(rng).unwrap();
+            if !matches!(board.game_move(col), Ok(GameMoveResult::Valid)) {
+                finished = true;
+                break;
+            }
+        }
+
+        if !finished && board.winning_moves(board.current_player).len() == 1 {
+            return board;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connect_four::test_rng::Lcg;
+
+    #[test]
+    fn mate_in_one_has_single_winning_move() {
+        let mut rng = Lcg(7);
+
+        for _ in 0..5 {
+            let board = generate_mate_in_one(&mut rng);
+            board.print();
+
+            let winning = board.winning_moves(board.current_player);
+            assert_eq!(winning.len(), 1);
+            assert_eq!(board.solve(), WIN_BASE - 1);
+
+            let mut solved = board.clone();
+            assert!(matches!(
+                solved.game_move(winning[0]),
+                Ok(GameMoveResult::Won(_))
+            ));
+        }
+    }
+}
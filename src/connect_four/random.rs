@@ -0,0 +1,122 @@
+use super::*;
+
+/// A minimal source of randomness, so the crate doesn't depend on a particular RNG.
+pub trait RandomSource {
+    /// Returns an index in the range `0..bound`. `bound` is never zero.
+    fn next_index(&mut self, bound: usize) -> usize;
+}
+
+/// Adapts any `rand::Rng` into a `RandomSource`.
+#[cfg(feature = "rand")]
+pub struct RngSource<R>(pub R);
+
+#[cfg(feature = "rand")]
+impl<R: rand::Rng> RandomSource for RngSource<R> {
+    fn next_index(&mut self, bound: usize) -> usize {
+        self.0.gen_range(0..bound)
+    }
+}
+
+/// Picks Red or Yellow with equal probability to start a game. A seeded source gives a
+/// reproducible schedule of who starts.
+pub fn random_starting_player(rng: &mut impl RandomSource) -> Player {
+    match rng.next_index(2) {
+        0 => Player::Red,
+        _ => Player::Yellow,
+    }
+}
+
+impl Board {
+    /// Picks a random column that can still be played, or None if the board is full.
+    pub fn random_move(&self, rng: &mut impl RandomSource) -> Option<usize> {
+        let moves = self.legal_moves();
+        if moves.is_empty() {
+            return None;
+        }
+        Some(moves[rng.next_index(moves.len())])
+    }
+
+    /// Plays uniformly random legal moves on a copy of the board until the game ends and
+    /// returns how it ended, as a rollout for Monte Carlo search. The board itself is left
+    /// untouched.
+    pub fn random_playout(&self, rng: &mut impl RandomSource) -> GameStatus {
+        let mut board = self.clone();
+        while board.status() == GameStatus::InProgress {
+            match board.random_move(rng) {
+                Some(col) => {
+                    board.game_move(col).unwrap();
+                }
+                None => break,
+            }
+        }
+        board.status()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connect_four::test_rng::Lcg;
+
+    /// Replays a fixed list of indices, wrapping them into the requested bound.
+    struct Sequence(Vec<usize>, usize);
+
+    impl RandomSource for Sequence {
+        fn next_index(&mut self, bound: usize) -> usize {
+            let index = self.0[self.1 % self.0.len()] % bound;
+            self.1 += 1;
+            index
+        }
+    }
+
+    #[test]
+    fn starting_player_is_evenly_drawn() {
+        let mut rng = Lcg(11);
+        let draws = 10_000;
+        let red = (0..draws)
+            .filter(|_| random_starting_player(&mut rng) == Player::Red)
+            .count();
+        assert!(
+            (4_500..=5_500).contains(&red),
+            "{} of {} were Red",
+            red,
+            draws
+        );
+
+        let mut rng = Sequence(vec![0, 1], 0);
+        assert_eq!(random_starting_player(&mut rng), Player::Red);
+        assert_eq!(random_starting_player(&mut rng), Player::Yellow);
+    }
+
+    #[test]
+    fn random_move_skips_full_columns() {
+        let mut board = Board::new(Player::Red);
+        for _ in 0..6 {
+            board.game_move(0).unwrap();
+        }
+
+        let mut rng = Sequence(vec![0, 5], 0);
+
+        assert_eq!(board.random_move(&mut rng), Some(1));
+        assert_eq!(board.random_move(&mut rng), Some(6));
+    }
+
+    #[test]
+    fn random_playout_finishes_the_game() {
+        let mut board = Board::new(Player::Red);
+        board.force_place(0, 0, Player::Red);
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+
+        // Picking the fourth legal column completes Red's bottom row straight away
+        let mut rng = Sequence(vec![3], 0);
+        assert_eq!(board.random_playout(&mut rng), GameStatus::Won(Player::Red));
+        assert_eq!(board.moves.len(), 0);
+
+        let mut rng = Sequence(vec![3, 1, 4, 1, 5, 9, 2, 6], 0);
+        assert_ne!(
+            Board::new(Player::Red).random_playout(&mut rng),
+            GameStatus::InProgress
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use super::*;
+
+/// Decides how each cell looks when the board is drawn with `render_with`, so frontends can
+/// use their own glyphs, colours or markup.
+pub trait CellRenderer {
+    fn render(&self, cell: Cell) -> String;
+}
+
+/// The faces `print` uses, padded to line up under the column numbers.
+pub struct EmojiRenderer;
+
+impl CellRenderer for EmojiRenderer {
+    fn render(&self, cell: Cell) -> String {
+        match cell {
+            Cell::Player(Player::Red) => "😈  ",
+            Cell::Player(Player::Yellow) => "😳  ",
+            Cell::Empty => " _  ",
+        }
+        .to_string()
+    }
+}
+
+/// Plain text cells for terminals without emoji: `X` for Red, `O` for Yellow and `.` for an
+/// empty cell.
+pub struct AsciiRenderer;
+
+impl CellRenderer for AsciiRenderer {
+    fn render(&self, cell: Cell) -> String {
+        match cell {
+            Cell::Player(Player::Red) => "X",
+            Cell::Player(Player::Yellow) => "O",
+            Cell::Empty => ".",
+        }
+        .to_string()
+    }
+}
+
+impl Board {
+    /// Draws the board with the given renderer, one line per row from the top of the board
+    /// down, ending each line with a newline. With `flip_for_view` set the board is turned
+    /// around for a player sitting on the other side: the bottom row comes first and the
+    /// columns run from right to left.
+    pub fn render_with<R: CellRenderer>(&self, renderer: &R) -> String {
+        let mut text = String::new();
+        for row in self.view_rows() {
+            for col in self.view_cols() {
+                text.push_str(&renderer.render(self.board[row][col]));
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Draws the board as printed by `print`, with the column numbers underneath in the order
+    /// the columns are drawn.
+    pub(crate) fn render(&self) -> String {
+        let mut text = self.render_with(&EmojiRenderer);
+        text.push('\n');
+        for col in self.view_cols() {
+            text.push_str(&format!(" {}  ", col + 1));
+        }
+        text.push_str("\n\n");
+        text
+    }
+
+    fn view_rows(&self) -> Vec<usize> {
+        let mut rows: Vec<usize> = (0..self.rows).rev().collect();
+        if self.flip_for_view {
+            rows.reverse();
+        }
+        rows
+    }
+
+    fn view_cols(&self) -> Vec<usize> {
+        let mut cols: Vec<usize> = (0..self.cols).collect();
+        if self.flip_for_view {
+            cols.reverse();
+        }
+        cols
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wraps each disc in a span for embedding in a web page.
+    struct HtmlRenderer;
+
+    impl CellRenderer for HtmlRenderer {
+        fn render(&self, cell: Cell) -> String {
+            match cell {
+                Cell::Player(p) => format!("<span class=\"{:?}\"></span>", p).to_lowercase(),
+                Cell::Empty => "<span></span>".to_string(),
+            }
+        }
+    }
+
+    #[test]
+    fn render_with_custom_renderers() {
+        let mut board = Board::new(Player::Red);
+        board.game_move(3).unwrap();
+        board.game_move(4).unwrap();
+
+        let ascii = board.render_with(&AsciiRenderer);
+        assert_eq!(ascii.lines().count(), 6);
+        assert_eq!(ascii.lines().last(), Some("...XO.."));
+
+        let html = board.render_with(&HtmlRenderer);
+        assert!(html.contains("<span class=\"red\"></span><span class=\"yellow\"></span>"));
+        assert_eq!(html.matches("<span>").count(), 40);
+    }
+
+    #[test]
+    fn flip_for_view_turns_the_drawing_around() {
+        let mut board = Board::new(Player::Red);
+        board.game_move(0).unwrap();
+        board.game_move(1).unwrap();
+
+        let lines: Vec<String> = board.render().lines().map(String::from).collect();
+        assert_eq!(lines[0], " _  ".repeat(7));
+        assert!(lines[5].starts_with("😈  😳   _  "));
+        assert_eq!(lines[7], " 1   2   3   4   5   6   7  ");
+
+        board.flip_for_view = true;
+        let flipped: Vec<String> = board.render().lines().map(String::from).collect();
+        assert!(flipped[0].ends_with(" _  😳  😈  "));
+        assert_eq!(flipped[5], " _  ".repeat(7));
+        assert_eq!(flipped[7], " 7   6   5   4   3   2   1  ");
+
+        // Moves still use the board's own columns
+        board.game_move(0).unwrap();
+        assert_eq!(board.board[1][0], Cell::Player(Player::Red));
+    }
+}
@@ -0,0 +1,531 @@
+use super::*;
+
+/// Score `solve` gives to a win on the very next move. Wins further away score one less for
+/// every extra ply, and losses score the negative of the same values.
+pub const WIN_BASE: i32 = 1_000_000;
+
+/// Number of entries in the transposition table `solve` creates for each call, about 3 MB.
+pub const DEFAULT_TABLE_ENTRIES: usize = 1 << 17;
+
+/// How a move compares to the best move available in the same position.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum MoveQuality {
+    Best,
+    Good,
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl Board {
+    /// Solves the position with a full game tree search. Returns 0 if best play ends in a
+    /// stalemate, `WIN_BASE - plies` if the current player can force a win with their disc
+    /// landing `plies` plies from now, and the negative of that if they are forced to lose.
+    /// Counting plies makes the winner prefer the fastest win and the loser the slowest loss.
+    /// A game that is already over scores `WIN_BASE` if the current player has won, the negative
+    /// of that if they have lost and 0 for a stalemate.
+    pub fn solve(&self) -> i32 {
+        self.solve_with(&mut TranspositionTable::new(DEFAULT_TABLE_ENTRIES))
+    }
+
+    /// Solves the position like `solve`, caching results in the given table. Reusing a table
+    /// across calls on related positions saves repeating work, and its capacity bounds the
+    /// memory the search uses.
+    pub fn solve_with(&self, table: &mut TranspositionTable) -> i32 {
+        if !self.stop_on_win {
+            return self.stopping_at_wins().solve_with(table);
+        }
+        self.negamax(0, -WIN_BASE, WIN_BASE, table)
+    }
+
+    /// Returns how many plies away the current player's forced win is, counting both players'
+    /// moves and including the winning one, or None if they can't force a win.
+    pub fn win_distance(&self) -> Option<u32> {
+        let score = self.solve();
+        if score > 0 {
+            Some((WIN_BASE - score) as u32)
+        } else {
+            None
+        }
+    }
+
+    /// Returns one line of best play leading to the current player's forced win, starting with
+    /// their move and ending with the winning disc, or None if they can't force a win. The
+    /// winner takes the fastest win at every turn and the loser holds out the longest, so the
+    /// line is as short as the win allows. Also None once the game is over.
+    pub fn winning_sequence(&self) -> Option<Vec<usize>> {
+        if self.status() != GameStatus::InProgress {
+            return None;
+        }
+        let mut table = TranspositionTable::new(DEFAULT_TABLE_ENTRIES);
+        if self.solve_with(&mut table) <= 0 {
+            return None;
+        }
+
+        let mut board = self.stopping_at_wins();
+        let mut line = Vec::new();
+        while board.status() == GameStatus::InProgress {
+            // Every move scores as the negation of the position it leaves, so the best move
+            // leaves the position that is worst for the player who replies
+            let col = board.legal_moves().into_iter().max_by_key(|&col| {
+                let mut child = board.clone();
+                match child.game_move(col) {
+                    Ok(GameMoveResult::Won(_)) => WIN_BASE,
+                    Ok(GameMoveResult::Valid) => parent_score(child.solve_with(&mut table)),
+                    _ => 0,
+                }
+            })?;
+            board.game_move(col).unwrap();
+            line.push(col);
+        }
+        Some(line)
+    }
+
+    /// Returns true unless the current player is forced to lose, meaning best play gets them at
+    /// least a stalemate.
+    pub fn can_force_draw(&self) -> bool {
+        self.solve() >= 0
+    }
+
+    /// Returns true when the game is in progress, the current player has at least one legal
+    /// move and every one of them leads to a forced loss.
+    pub fn all_moves_lose(&self) -> bool {
+        self.status() == GameStatus::InProgress
+            && !self.legal_moves().is_empty()
+            && self.solve() < 0
+    }
+
+    /// Classifies the last move played by comparing the solved value of the position before it
+    /// with the value the move left the mover with. Throwing away one step of the result, a win
+    /// into a stalemate or a stalemate into a loss, is a mistake and a win into a loss is a
+    /// blunder. Keeping the result but winning more slowly, or losing sooner, is good when it
+    /// costs up to four plies and an inaccuracy beyond that. A resignation after the move doesn't
+    /// change its value, only a connect four through its disc counts as a win. Returns None if
+    /// no moves have been played.
+    ///
+    /// Both positions are solved with a full game tree search, so the time taken grows
+    /// exponentially with the number of empty cells. That is quick on small boards and late in
+    /// a game, but early on a 6x7 board it can run for hours.
+    pub fn classify_last_move(&self) -> Option<MoveQuality> {
+        let record = *self.moves.last()?;
+        let mut before = self.clone();
+        before.undo()?;
+
+        let after = if self.has_won(record.row, record.col) {
+            WIN_BASE - 1
+        } else {
+            // Replaying the move on the earlier position leaves out anything that ended the game
+            // after it, such as a resignation
+            let mut after = before.clone();
+            match after.game_move(record.col) {
+                Ok(GameMoveResult::Valid) => parent_score(after.solve()),
+                _ => 0,
+            }
+        };
+        let best = before.solve();
+
+        Some(match (best.signum() - after.signum(), best - after) {
+            (0, 0) => MoveQuality::Best,
+            (0, 1..=4) => MoveQuality::Good,
+            (0, _) => MoveQuality::Inaccuracy,
+            (1, _) => MoveQuality::Mistake,
+            _ => MoveQuality::Blunder,
+        })
+    }
+
+    /// Returns the columns that let the current player force a win within the given number of
+    /// plies, counting both players' moves. With `plies` set to 1 this is the same as
+    /// `winning_moves` for the current player.
+    pub fn forced_wins_within(&self, plies: u32) -> Vec<usize> {
+        if !self.stop_on_win {
+            return self.stopping_at_wins().forced_wins_within(plies);
+        }
+        self.legal_moves()
+            .into_iter()
+            .filter(|&col| {
+                let mut child = self.clone();
+                match child.game_move(col) {
+                    Ok(GameMoveResult::Won(_)) => true,
+                    Ok(GameMoveResult::Valid) => plies >= 3 && child.loses_within(plies - 1),
+                    _ => false,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns true if every reply available to the current player lets the opponent force a
+    /// win within the remaining plies.
+    fn loses_within(&self, plies: u32) -> bool {
+        let moves = self.legal_moves();
+        !moves.is_empty()
+            && moves.into_iter().all(|col| {
+                let mut child = self.clone();
+                match child.game_move(col) {
+                    Ok(GameMoveResult::Valid) => !child.forced_wins_within(plies - 1).is_empty(),
+                    _ => false,
+                }
+            })
+    }
+
+    /// Returns a copy of the board with `stop_on_win` set, so searches treat the first connect
+    /// four as the end of the game even when play would carry on past it.
+    fn stopping_at_wins(&self) -> Board {
+        let mut board = self.clone();
+        board.stop_on_win = true;
+        board
+    }
+
+    /// Searches the position `ply` plies below the root of the search.
+    fn negamax(
+        &self,
+        ply: i32,
+        mut alpha: i32,
+        mut beta: i32,
+        table: &mut TranspositionTable,
+    ) -> i32 {
+        match self.status() {
+            GameStatus::InProgress => (),
+            GameStatus::Won(p) if p == self.current_player => return WIN_BASE - ply,
+            GameStatus::Won(_) => return -(WIN_BASE - ply),
+            GameStatus::Stalemate => return 0,
+        }
+
+        let moves = self.ordered_moves();
+        let mut children = Vec::with_capacity(moves.len());
+        for col in moves {
+            let mut child = self.clone();
+            match child.game_move(col) {
+                Ok(GameMoveResult::Won(_)) => return WIN_BASE - (ply + 1),
+                Ok(GameMoveResult::Valid | GameMoveResult::Stalemate) => children.push(child),
+                Err(_) => (),
+            }
+        }
+        if children.is_empty() {
+            return 0;
+        }
+
+        // Nothing here can win sooner than two plies from now or lose sooner than the
+        // opponent's next move, so narrow the window to what is still reachable
+        alpha = alpha.max(-(WIN_BASE - (ply + 2)));
+        beta = beta.min(WIN_BASE - (ply + 3));
+        if alpha >= beta {
+            return alpha;
+        }
+
+        let key = self.current_hash();
+        if let Some(entry) = table.get(key) {
+            let score = from_table(entry.score, ply);
+            match entry.bound {
+                Bound::Exact => return score,
+                Bound::Lower => alpha = alpha.max(score),
+                Bound::Upper => beta = beta.min(score),
+            }
+            if alpha >= beta {
+                return score;
+            }
+        }
+
+        // The number of empty cells bounds the size of the subtree below this position
+        let depth = (0..self.cols)
+            .map(|col| self.rows - self.column_height(col))
+            .sum::<usize>() as u32;
+        let original_alpha = alpha;
+        for child in children {
+            let score = -child.negamax(ply + 1, -beta, -alpha, table);
+            if score >= beta {
+                table.store(
+                    key,
+                    TableEntry {
+                        score: to_table(score, ply),
+                        depth,
+                        bound: Bound::Lower,
+                    },
+                );
+                return score;
+            }
+            alpha = alpha.max(score);
+        }
+        let bound = if alpha > original_alpha {
+            Bound::Exact
+        } else {
+            Bound::Upper
+        };
+        table.store(
+            key,
+            TableEntry {
+                score: to_table(alpha, ply),
+                depth,
+                bound,
+            },
+        );
+        alpha
+    }
+}
+
+/// Converts a score counted from the root of the search into one counted from the position
+/// `ply` plies below it, so the stored value holds wherever the position comes up again.
+fn to_table(score: i32, ply: i32) -> i32 {
+    match score.signum() {
+        1 => score + ply,
+        -1 => score - ply,
+        _ => 0,
+    }
+}
+
+/// Reverses `to_table` for a position found `ply` plies below the root.
+fn from_table(score: i32, ply: i32) -> i32 {
+    match score.signum() {
+        1 => score - ply,
+        -1 => score + ply,
+        _ => 0,
+    }
+}
+
+/// Converts a solved score for the position after a move into the score for the player who
+/// made the move, one ply further from the end of the game.
+fn parent_score(score: i32) -> i32 {
+    match score.signum() {
+        1 => -(score - 1),
+        -1 => -(score + 1),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_moves_lose_against_open_three() {
+        let mut board = Board::new(Player::Yellow);
+
+        // Red threatens both ends of the bottom row, Yellow can only block one of them
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+        board.force_place(0, 3, Player::Red);
+        board.force_place(1, 2, Player::Yellow);
+        board.force_place(1, 3, Player::Yellow);
+
+        board.print();
+
+        assert_eq!(board.solve(), -(WIN_BASE - 2));
+        assert!(board.all_moves_lose());
+        assert!(!board.can_force_draw());
+    }
+
+    #[test]
+    fn all_moves_lose_with_winning_move() {
+        let mut board = Board::new(Player::Red);
+
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+        board.force_place(0, 3, Player::Red);
+        board.force_place(1, 2, Player::Yellow);
+        board.force_place(1, 3, Player::Yellow);
+
+        assert_eq!(board.solve(), WIN_BASE - 1);
+        assert!(!board.all_moves_lose());
+    }
+
+    #[test]
+    fn forced_wins_within_finds_open_three_setup() {
+        let mut board = Board::new(Player::Red);
+
+        board.force_place(0, 2, Player::Red);
+        board.force_place(0, 3, Player::Red);
+        board.force_place(1, 3, Player::Yellow);
+
+        assert!(board.forced_wins_within(1).is_empty());
+        assert_eq!(board.forced_wins_within(3), vec![1, 4]);
+    }
+
+    #[test]
+    fn forced_wins_within_one_ply() {
+        let mut board = Board::new(Player::Yellow);
+
+        board.force_place(0, 5, Player::Yellow);
+        board.force_place(1, 5, Player::Yellow);
+        board.force_place(2, 5, Player::Yellow);
+
+        assert_eq!(board.forced_wins_within(1), vec![5]);
+    }
+
+    #[test]
+    fn classify_last_move_flags_blunder() {
+        let mut board = Board::new(Player::Red);
+        assert_eq!(board.classify_last_move(), None);
+
+        board.force_place(0, 0, Player::Red);
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+        board.force_place(0, 6, Player::Yellow);
+        board.force_place(1, 6, Player::Yellow);
+        board.force_place(2, 6, Player::Yellow);
+
+        let mut winning = board.clone();
+        winning.game_move(3).unwrap();
+        assert_eq!(winning.classify_last_move(), Some(MoveQuality::Best));
+
+        board.game_move(5).unwrap();
+        assert_eq!(board.classify_last_move(), Some(MoveQuality::Blunder));
+
+        // Yellow resigning hands Red the game but doesn't redeem Red's move
+        board.resign();
+        assert_eq!(board.classify_last_move(), Some(MoveQuality::Blunder));
+    }
+
+    #[test]
+    fn finished_games_solve_without_searching() {
+        let mut board = Board::new(Player::Red);
+        board.force_place(0, 0, Player::Red);
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+        board.game_move(3).unwrap();
+
+        assert_eq!(board.solve(), WIN_BASE);
+        assert_eq!(board.win_distance(), Some(0));
+        assert_eq!(board.winning_sequence(), None);
+        assert!(board.can_force_draw());
+
+        let mut resigned = Board::new(Player::Red);
+        resigned.game_move(3).unwrap();
+        resigned.resign();
+        assert_eq!(resigned.solve(), -WIN_BASE);
+        assert!(!resigned.all_moves_lose());
+        assert!(!resigned.can_force_draw());
+    }
+
+    #[test]
+    fn win_distance_counts_plies() {
+        let mut board = Board::new(Player::Red);
+
+        // Red can win straight away on either end of the bottom row or on top of the third column
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Red);
+        board.force_place(0, 3, Player::Red);
+        board.force_place(1, 2, Player::Red);
+        board.force_place(2, 2, Player::Red);
+        board.force_place(1, 3, Player::Yellow);
+        assert_eq!(board.win_distance(), Some(1));
+    }
+
+    #[test]
+    fn winning_sequence_plays_out_the_win() {
+        let mut board = BoardBuilder::new()
+            .rows(3)
+            .cols(4)
+            .win_length(3)
+            .build()
+            .unwrap();
+        board.force_place(0, 1, Player::Red);
+        board.force_place(1, 1, Player::Yellow);
+
+        let line = board.winning_sequence().unwrap();
+        assert_eq!(line.len() as u32, board.win_distance().unwrap());
+
+        let mut played = board.clone();
+        for col in line {
+            played.game_move(col).unwrap();
+        }
+        assert_eq!(played.status(), GameStatus::Won(Player::Red));
+
+        played.undo();
+        played.undo();
+        assert_eq!(played.winning_sequence(), None);
+    }
+
+    #[test]
+    fn searches_stop_at_wins_without_stop_on_win() {
+        let mut board = BoardBuilder::new()
+            .rows(3)
+            .cols(4)
+            .win_length(3)
+            .build()
+            .unwrap();
+        board.stop_on_win = false;
+        board.force_place(0, 0, Player::Red);
+        board.force_place(0, 1, Player::Red);
+        board.force_place(1, 0, Player::Yellow);
+        board.force_place(1, 1, Player::Yellow);
+
+        assert_eq!(board.winning_moves(Player::Red), vec![2]);
+        assert_eq!(board.solve(), WIN_BASE - 1);
+        assert_eq!(board.forced_wins_within(1), vec![2]);
+        assert_eq!(board.winning_sequence(), Some(vec![2]));
+        assert!(!board.stop_on_win);
+    }
+
+    #[test]
+    fn win_distance_takes_the_shorter_win() {
+        let mut board = BoardBuilder::new()
+            .rows(4)
+            .cols(5)
+            .win_length(3)
+            .build()
+            .unwrap();
+        for col in [1, 1, 2, 3] {
+            board.game_move(col).unwrap();
+        }
+
+        // Red wins at once in column 0 and can also force a win in three plies from column 2
+        assert_eq!(board.forced_wins_within(1), vec![0]);
+        assert_eq!(board.forced_wins_within(3), vec![0, 2]);
+        assert_eq!(board.win_distance(), Some(1));
+        assert_eq!(board.solve(), WIN_BASE - 1);
+        assert_eq!(board.winning_sequence(), Some(vec![0]));
+    }
+
+    #[test]
+    fn win_distance_on_small_board() {
+        let mut board = BoardBuilder::new()
+            .rows(3)
+            .cols(4)
+            .win_length(3)
+            .build()
+            .unwrap();
+
+        board.force_place(0, 1, Player::Red);
+        board.force_place(1, 1, Player::Yellow);
+        assert_eq!(board.win_distance(), Some(3));
+    }
+
+    #[test]
+    fn can_force_draw_on_small_boards() {
+        let winnable = BoardBuilder::new()
+            .rows(3)
+            .cols(4)
+            .win_length(3)
+            .build()
+            .unwrap();
+        assert!(winnable.can_force_draw());
+
+        let drawn = BoardBuilder::new()
+            .rows(2)
+            .cols(3)
+            .win_length(3)
+            .build()
+            .unwrap();
+        assert_eq!(drawn.solve(), 0);
+        assert!(drawn.can_force_draw());
+    }
+
+    #[test]
+    fn solve_with_tiny_table_agrees() {
+        let mut board = BoardBuilder::new()
+            .rows(4)
+            .cols(4)
+            .win_length(3)
+            .build()
+            .unwrap();
+        board.force_place(0, 1, Player::Red);
+        board.force_place(0, 2, Player::Yellow);
+
+        let mut table = TranspositionTable::new(1);
+        assert_eq!(board.solve_with(&mut table), board.solve());
+
+        // A table reused from the first solve gives the same answer again
+        let mut table = TranspositionTable::new(1 << 10);
+        let score = board.solve_with(&mut table);
+        assert_eq!(board.solve_with(&mut table), score);
+    }
+}
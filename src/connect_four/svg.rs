@@ -0,0 +1,63 @@
+use super::*;
+use std::fmt::Write;
+
+const CELL_SIZE: usize = 60;
+const RADIUS: usize = 24;
+
+impl Board {
+    /// Renders the board as an SVG image: a blue grid with red and yellow discs and white holes
+    /// for empty cells. The top row of the image is the top row of the board, matching `print`.
+    pub fn to_svg(&self) -> String {
+        let width = self.cols * CELL_SIZE;
+        let height = self.rows * CELL_SIZE;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" \
+             viewBox=\"0 0 {0} {1}\">\n<rect width=\"{0}\" height=\"{1}\" fill=\"#1e4fd8\"/>\n",
+            width, height
+        );
+
+        for (i, row) in self.board.iter().rev().enumerate() {
+            for (j, cell) in row.iter().enumerate() {
+                let fill = match cell {
+                    Cell::Player(Player::Red) => "#e02020",
+                    Cell::Player(Player::Yellow) => "#f5d000",
+                    Cell::Empty => "#ffffff",
+                };
+                let _ = writeln!(
+                    svg,
+                    "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>",
+                    j * CELL_SIZE + CELL_SIZE / 2,
+                    i * CELL_SIZE + CELL_SIZE / 2,
+                    RADIUS,
+                    fill
+                );
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_svg_draws_every_cell() {
+        let mut board = Board::new(Player::Red);
+        board.game_move(3).unwrap();
+        board.game_move(3).unwrap();
+
+        let svg = board.to_svg();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<circle").count(), 42);
+        assert_eq!(svg.matches("#e02020").count(), 1);
+        assert_eq!(svg.matches("#f5d000").count(), 1);
+
+        // The bottom row is drawn last, with Red's disc in the fourth column
+        assert!(svg.contains("<circle cx=\"210\" cy=\"330\" r=\"24\" fill=\"#e02020\"/>"));
+        assert!(svg.contains("<circle cx=\"210\" cy=\"270\" r=\"24\" fill=\"#f5d000\"/>"));
+    }
+}
@@ -0,0 +1,94 @@
+use super::*;
+
+/// A well-known tactical shape, reported by `recognized_tactics` for commentary.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TacticName {
+    /// Two or more columns that win straight away, so the opponent can only block one.
+    DoubleThreat,
+    /// Two empty cells directly on top of each other that would both connect four. Blocking
+    /// the lower one hands over the upper one. A "7" of discs, a row of three with a diagonal
+    /// hanging down from one end, is the classic way to build it.
+    SevenTrap,
+}
+
+/// Returns true if the player has the tactic set up on the board.
+type Detector = fn(&Board, Player) -> bool;
+
+/// Every tactic with the check that spots it, in the order they are reported.
+const DETECTORS: [(TacticName, Detector); 2] = [
+    (TacticName::DoubleThreat, has_double_threat),
+    (TacticName::SevenTrap, has_stacked_threats),
+];
+
+impl Board {
+    /// Returns the tactics the player has set up on the board, in the order they are declared
+    /// in `TacticName`.
+    pub fn recognized_tactics(&self, player: Player) -> Vec<TacticName> {
+        DETECTORS
+            .iter()
+            .filter(|(_, detect)| detect(self, player))
+            .map(|&(name, _)| name)
+            .collect()
+    }
+}
+
+fn has_double_threat(board: &Board, player: Player) -> bool {
+    let (red, yellow) = board.all_immediate_wins();
+    match player {
+        Player::Red => red.len() >= 2,
+        Player::Yellow => yellow.len() >= 2,
+    }
+}
+
+fn has_stacked_threats(board: &Board, player: Player) -> bool {
+    (0..board.cols).any(|col| {
+        (1..board.rows).any(|row| {
+            [row - 1, row].iter().all(|&r| {
+                board.board[r][col] == Cell::Empty && board.completes_line(r, col, player)
+            })
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_seven_trap() {
+        let mut board = Board::new(Player::Yellow);
+
+        // Red's row of three on the third row and the diagonal down from its right end both
+        // end in column 4, one on top of the other
+        board.force_place(0, 1, Player::Red);
+        board.force_place(1, 1, Player::Yellow);
+        board.force_place(2, 1, Player::Red);
+        board.force_place(0, 2, Player::Yellow);
+        board.force_place(1, 2, Player::Red);
+        board.force_place(2, 2, Player::Red);
+        board.force_place(0, 3, Player::Yellow);
+        board.force_place(1, 3, Player::Yellow);
+        board.force_place(2, 3, Player::Red);
+        board.print();
+
+        assert_eq!(
+            board.recognized_tactics(Player::Red),
+            vec![TacticName::SevenTrap]
+        );
+        assert!(board.recognized_tactics(Player::Yellow).is_empty());
+    }
+
+    #[test]
+    fn recognizes_double_threat() {
+        let mut board = Board::new(Player::Yellow);
+
+        board.force_place(0, 2, Player::Red);
+        board.force_place(0, 3, Player::Red);
+        board.force_place(0, 4, Player::Red);
+
+        assert_eq!(
+            board.recognized_tactics(Player::Red),
+            vec![TacticName::DoubleThreat]
+        );
+    }
+}
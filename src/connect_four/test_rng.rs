@@ -0,0 +1,14 @@
+use super::*;
+
+/// A linear congruential generator for tests that need a long, repeatable stream of indices.
+pub(crate) struct Lcg(pub u64);
+
+impl RandomSource for Lcg {
+    fn next_index(&mut self, bound: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) as usize) % bound
+    }
+}
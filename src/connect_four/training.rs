@@ -0,0 +1,163 @@
+use std::collections::HashSet;
+
+use super::*;
+
+/// A position and the column chosen from it, in a plain form that can be written out one
+/// sample per line with `to_row` and read back with `from_row`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TrainingSample {
+    /// The cells of the board in the order of `as_flat`, row by row from the bottom left.
+    pub cells: Vec<Cell>,
+    pub player: Player,
+    pub column: usize,
+}
+
+impl TrainingSample {
+    /// Takes the cells and player to move of the board along with the chosen column.
+    pub fn new(board: &Board, column: usize) -> TrainingSample {
+        TrainingSample {
+            cells: board.as_flat(),
+            player: board.current_player,
+            column,
+        }
+    }
+
+    /// Writes the sample as comma-separated values: `x` for a Red disc, `o` for a Yellow disc
+    /// and `b` for an empty cell, then `x` or `o` for the player to move and finally the
+    /// zero-based column.
+    pub fn to_row(&self) -> String {
+        let mut values: Vec<&str> = self
+            .cells
+            .iter()
+            .map(|&cell| match cell {
+                Cell::Player(p) => player_value(p),
+                Cell::Empty => "b",
+            })
+            .collect();
+        values.push(player_value(self.player));
+        format!("{},{}", values.join(","), self.column)
+    }
+
+    /// Reads a sample written by `to_row`.
+    pub fn from_row(row: &str) -> Result<TrainingSample, String> {
+        let mut values: Vec<&str> = row.trim().split(',').map(str::trim).collect();
+        let column = values
+            .pop()
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| format!("{:?} doesn't end with a column", row))?;
+        let player = match values.pop() {
+            Some("x") => Player::Red,
+            Some("o") => Player::Yellow,
+            other => return Err(format!("Unknown player {:?}", other)),
+        };
+        let cells = values
+            .into_iter()
+            .map(|value| match value {
+                "x" => Ok(Cell::Player(Player::Red)),
+                "o" => Ok(Cell::Player(Player::Yellow)),
+                "b" => Ok(Cell::Empty),
+                other => Err(format!("Unknown cell value {:?}", other)),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(TrainingSample {
+            cells,
+            player,
+            column,
+        })
+    }
+}
+
+/// The letter `TrainingSample` rows use for a player's discs.
+fn player_value(player: Player) -> &'static str {
+    match player {
+        Player::Red => "x",
+        Player::Yellow => "o",
+    }
+}
+
+/// Plays `games` games of the AI against itself from an empty board and returns every
+/// position the AI moved from together with the column it chose. To vary the games, one move in
+/// five is replaced by a random move; those moves are played but not recorded. Convert the
+/// pairs with `TrainingSample::new` to write them out.
+pub fn generate_self_play(
+    games: usize,
+    depth: u32,
+    rng: &mut impl RandomSource,
+) -> Vec<(Board, usize)> {
+    let mut samples = Vec::new();
+    for _ in 0..games {
+        let mut board = Board::new(Player::Red);
+        while board.status() == GameStatus::InProgress {
+            let col = if rng.next_index(5) == 0 {
+                board.random_move(rng)
+            } else {
+                let col = board.best_move(depth);
+                if let Some(col) = col {
+                    samples.push((board.clone(), col));
+                }
+                col
+            };
+            match col {
+                Some(col) => board.game_move(col).unwrap(),
+                None => break,
+            };
+        }
+    }
+    samples
+}
+
+/// Removes samples whose position and player to move have already been seen, keeping the
+/// first occurrence.
+pub fn dedup_positions(samples: &mut Vec<(Board, usize)>) {
+    let mut seen = HashSet::new();
+    samples.retain(|(board, _)| seen.insert((board.current_player, board.as_flat())));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connect_four::test_rng::Lcg;
+
+    #[test]
+    fn self_play_records_legal_moves() {
+        let mut rng = Lcg(3);
+        let mut samples = generate_self_play(3, 2, &mut rng);
+
+        assert!(!samples.is_empty());
+        for (board, col) in &samples {
+            assert_eq!(board.status(), GameStatus::InProgress);
+            assert!(board.legal_moves().contains(col));
+        }
+
+        // Every game opens from the same empty board, so dedup keeps only one of those
+        let openings = samples
+            .iter()
+            .filter(|(board, _)| board.moves.is_empty())
+            .count();
+        dedup_positions(&mut samples);
+        let deduped = samples
+            .iter()
+            .filter(|(board, _)| board.moves.is_empty())
+            .count();
+        assert_eq!(deduped, openings.min(1));
+    }
+
+    #[test]
+    fn samples_round_trip_through_rows() {
+        let mut rng = Lcg(5);
+        for (board, col) in generate_self_play(2, 1, &mut rng) {
+            let sample = TrainingSample::new(&board, col);
+            assert_eq!(TrainingSample::from_row(&sample.to_row()), Ok(sample));
+        }
+
+        let mut board = Board::new(Player::Red);
+        board.game_move(0).unwrap();
+        let row = TrainingSample::new(&board, 3).to_row();
+        assert!(row.starts_with("x,b,"));
+        assert!(row.ends_with(",b,o,3"));
+
+        assert!(TrainingSample::from_row("x,b,o").is_err());
+        assert!(TrainingSample::from_row("x,b,q,3").is_err());
+        assert!(TrainingSample::from_row("x,q,b,x,3").is_err());
+    }
+}
@@ -0,0 +1,46 @@
+use super::*;
+
+impl Board {
+    /// Returns a copy of the board turned upside down, so the cell at `(row, col)` moves to
+    /// `(rows - 1 - row, cols - 1 - col)`. The discs end up hanging from the top of the board,
+    /// which breaks gravity, so the result is meant for analysis and data augmentation rather
+    /// than for continuing the game. The copy keeps the player to move but no move history.
+    pub fn rotate_180(&self) -> Board {
+        let mut rotated =
+            Board::with_dimensions(self.current_player, self.rows, self.cols, self.win_length);
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                rotated.update_cell(
+                    self.rows - 1 - row,
+                    self.cols - 1 - col,
+                    self.board[row][col],
+                );
+            }
+        }
+        rotated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotate_180_maps_corners() {
+        let mut board = Board::new(Player::Yellow);
+
+        board.force_place(0, 0, Player::Red);
+        board.force_place(0, 1, Player::Yellow);
+        board.force_place(1, 0, Player::Yellow);
+
+        let rotated = board.rotate_180();
+        rotated.print();
+
+        assert_eq!(rotated.board[5][6], Cell::Player(Player::Red));
+        assert_eq!(rotated.board[5][5], Cell::Player(Player::Yellow));
+        assert_eq!(rotated.board[4][6], Cell::Player(Player::Yellow));
+        assert_eq!(rotated.board[0][0], Cell::Empty);
+        assert_eq!(rotated.current_player, Player::Yellow);
+        assert_eq!(rotated.rotate_180().board, board.board);
+    }
+}
@@ -0,0 +1,106 @@
+/// Which side of the true score a stored score sits on. Alpha-beta only learns the exact
+/// score of a position when it falls inside the search window.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Bound {
+    Exact,
+    /// The true score is at least the stored one.
+    Lower,
+    /// The true score is at most the stored one.
+    Upper,
+}
+
+/// A stored search result. `depth` measures how much work went into it, so deeper results
+/// are kept over shallower ones when two positions share a slot.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct TableEntry {
+    pub score: i32,
+    pub depth: u32,
+    pub bound: Bound,
+}
+
+/// A fixed-size cache of search results keyed by Zobrist hash. The table never grows: each
+/// hash maps to a single slot, and storing into a taken slot only replaces the old entry if
+/// the new one is for the same position or was searched at least as deep.
+pub struct TranspositionTable {
+    slots: Vec<Option<(u64, TableEntry)>>,
+    mask: usize,
+}
+
+impl TranspositionTable {
+    /// Creates a table with room for `capacity` entries, rounded down to a power of two and at
+    /// least one. Each entry takes `size_of::<Option<(u64, TableEntry)>>()` bytes, 24 on
+    /// common platforms, so 1 << 17 entries is about 3 MB.
+    pub fn new(capacity: usize) -> TranspositionTable {
+        let size = if capacity == 0 {
+            1
+        } else {
+            1 << capacity.ilog2()
+        };
+        TranspositionTable {
+            slots: vec![None; size],
+            mask: size - 1,
+        }
+    }
+
+    /// Returns the number of entries the table can hold.
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns the entry stored for the position with the given hash, if it is still there.
+    pub fn get(&self, key: u64) -> Option<TableEntry> {
+        match self.slots[key as usize & self.mask] {
+            Some((stored, entry)) if stored == key => Some(entry),
+            _ => None,
+        }
+    }
+
+    /// Stores an entry for the position with the given hash, unless its slot holds a deeper
+    /// result for another position.
+    pub fn store(&mut self, key: u64, entry: TableEntry) {
+        let slot = &mut self.slots[key as usize & self.mask];
+        match slot {
+            Some((stored, old)) if *stored != key && old.depth > entry.depth => (),
+            _ => *slot = Some((key, entry)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(score: i32, depth: u32) -> TableEntry {
+        TableEntry {
+            score,
+            depth,
+            bound: Bound::Exact,
+        }
+    }
+
+    #[test]
+    fn capacity_rounds_down_to_power_of_two() {
+        assert_eq!(TranspositionTable::new(0).capacity(), 1);
+        assert_eq!(TranspositionTable::new(1000).capacity(), 512);
+        assert_eq!(TranspositionTable::new(1024).capacity(), 1024);
+    }
+
+    #[test]
+    fn store_prefers_deeper_entries() {
+        let mut table = TranspositionTable::new(4);
+        table.store(1, entry(10, 5));
+        assert_eq!(table.get(1), Some(entry(10, 5)));
+        assert_eq!(table.get(2), None);
+
+        // 5 shares a slot with 1 but was searched less deeply
+        table.store(5, entry(20, 3));
+        assert_eq!(table.get(5), None);
+        assert_eq!(table.get(1), Some(entry(10, 5)));
+
+        table.store(1, entry(30, 1));
+        assert_eq!(table.get(1), Some(entry(30, 1)));
+        table.store(5, entry(20, 3));
+        assert_eq!(table.get(5), Some(entry(20, 3)));
+        assert_eq!(table.get(1), None);
+    }
+}
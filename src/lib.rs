@@ -0,0 +1,2 @@
+mod connect_four;
+pub use connect_four::*;
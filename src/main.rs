@@ -1,9 +1,30 @@
-
-mod connect_four;
 use connect_four::*;
+use std::io;
 
 fn main() {
     let mut game_board = Board::new(Player::Red);
+    game_board.names = match (ask_name("Red"), ask_name("Yellow")) {
+        (None, None) => None,
+        (red, yellow) => Some((
+            red.unwrap_or_else(|| "Red".to_string()),
+            yellow.unwrap_or_else(|| "Yellow".to_string()),
+        )),
+    };
     game_board.run_game();
 }
 
+/// Asks for a player's name, returning None if it was left blank.
+fn ask_name(colour: &str) -> Option<String> {
+    println!(
+        "Enter a name for {} (leave blank to use the colour):",
+        colour
+    );
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).ok()?;
+    let name = input.trim();
+    if name.is_empty() {
+        None
+    } else {
+        Some(name.to_string())
+    }
+}